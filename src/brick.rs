@@ -1,13 +1,14 @@
 // Copyright (c) 2024, Radu Racariu.
 
 use anyhow::{anyhow, Result};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use sophia::inmem::graph::FastGraph;
 use sophia_api::ns::Namespace;
 use sophia_api::term::SimpleTerm;
 use sophia_api::{
     graph::{Graph, MutableGraph},
-    ns::{rdf, rdfs},
+    ns::{owl, rdf, rdfs},
     prelude::TripleParser,
     term::matcher::Any,
     term::Term,
@@ -19,13 +20,30 @@ use sophia_rio::model::Trusted;
 use sophia_turtle::parser::turtle::TurtleParser;
 
 use crate::curie::Curie;
-use crate::entity::BrickEntity;
+use crate::entity::{BrickEntity, DeprecatedClass, Deprecation};
+use crate::hierarchy::{ClassHierarchy, ClassNode, ClassRelative};
+use crate::migration::{self, MigrationReport, PropertyRename, UnsupportedRule};
 use crate::namespaces::PrefixNamespaceMap;
-use crate::property::{BrickProperty, LogicalConstraint};
+use crate::property::{
+    BrickProperty, EntityPropertyDesc, InheritedProperty, LogicalConstraint, NodeShapeRef,
+    PropertyPairConstraint,
+};
+use crate::qudt::{self, DimensionVector, QuantityKind, Unit};
+use crate::relationship::{
+    AllowedRelationship, BrickRelationship, DomainRangeIndex, PropertyCharacteristic,
+};
+use crate::search::{SearchIndex, SearchResult};
+use crate::shape::NodeShape;
+use crate::suggest::{suggest_classes, Abbreviations, ClassSuggestion};
+use crate::tags::{TagIndex, TagInference, TagInfo};
 
 pub struct Brick {
     graph: FastGraph,
     prefixes: PrefixNamespaceMap,
+    hierarchy: ClassHierarchy,
+    tags: TagIndex,
+    search: SearchIndex,
+    domain_ranges: DomainRangeIndex,
 }
 
 impl Brick {
@@ -40,8 +58,114 @@ impl Brick {
         })?;
 
         let prefixes = PrefixNamespaceMap::new(parser.prefixes());
+        let hierarchy = ClassHierarchy::new(&graph, &prefixes)?;
+        let tags = TagIndex::new(&graph, &prefixes)?;
+        let domain_ranges = DomainRangeIndex::new(&graph, &prefixes)?;
+
+        let mut brick = Brick {
+            graph,
+            prefixes,
+            hierarchy,
+            tags,
+            search: SearchIndex::default(),
+            domain_ranges,
+        };
+        brick.search = SearchIndex::new(&brick.graph, &brick.prefixes, |class| {
+            brick.canonical_class(class)
+        })?;
+
+        Ok(brick)
+    }
+
+    /// Load more Turtle in the ontology graph, such as the QUDT unit and
+    /// quantity kind vocabularies. The prefixes already known are kept.
+    pub fn load(&mut self, input: &str) -> Result<()> {
+        let mut parser = TurtleParser { base: None }.parse_str(input).0;
+
+        parser.parse_all(&mut |triple| {
+            self.graph.insert_triple(Trusted(triple))?;
+            anyhow::Ok(())
+        })?;
+
+        self.prefixes.extend(parser.prefixes());
+        self.hierarchy = ClassHierarchy::new(&self.graph, &self.prefixes)?;
+        self.tags = TagIndex::new(&self.graph, &self.prefixes)?;
+        self.search = SearchIndex::new(&self.graph, &self.prefixes, |class| {
+            self.canonical_class(class)
+        })?;
+        self.domain_ranges = DomainRangeIndex::new(&self.graph, &self.prefixes)?;
+
+        Ok(())
+    }
+
+    /// All the classes declared as `owl:Class`.
+    pub fn classes(&self) -> Result<Vec<Curie>> {
+        self.instances_of(owl::Class)
+    }
+
+    /// Check if a class is declared as `owl:Class`.
+    pub fn is_class(&self, curie: &Curie) -> Result<bool> {
+        let class = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+
+        Ok(self
+            .graph
+            .triples_matching([class], [rdf::type_], [owl::Class])
+            .next()
+            .is_some())
+    }
+
+    /// All the relationships declared as `owl:ObjectProperty`.
+    pub fn object_properties(&self) -> Result<Vec<Curie>> {
+        self.instances_of(owl::ObjectProperty)
+    }
+
+    /// All the properties declared as `owl:DatatypeProperty`.
+    pub fn datatype_properties(&self) -> Result<Vec<Curie>> {
+        self.instances_of(owl::DatatypeProperty)
+    }
+
+    /// The classes that have no superclass among the declared classes.
+    pub fn root_classes(&self) -> Result<Vec<Curie>> {
+        let classes = self.classes()?;
+
+        let mut roots = Vec::new();
+        for class in &classes {
+            if !self
+                .super_classes_of(class)?
+                .iter()
+                .any(|super_class| classes.binary_search(super_class).is_ok())
+            {
+                roots.push(class.clone());
+            }
+        }
+
+        Ok(roots)
+    }
+
+    /// The top level Brick categories, the direct subclasses of `brick:Class`,
+    /// such as Point, Equipment, Location or Collection.
+    pub fn top_level_categories(&self) -> Result<Vec<Curie>> {
+        let mut categories = self.sub_classes_of(&Curie::new("brick", "Class"))?;
+        categories.sort();
+
+        Ok(categories)
+    }
+
+    /// The declared instances of a class. Blank nodes and IRIs outside the
+    /// known namespaces are skipped, as the indexes do.
+    fn instances_of<T: Term>(&self, class: T) -> Result<Vec<Curie>> {
+        let mut instances = Vec::new();
+
+        for triple in self.graph.triples_matching(Any, [&rdf::type_], [class]) {
+            if let Ok(curie) = Curie::from_term(triple?.s(), &self.prefixes) {
+                instances.push(curie);
+            }
+        }
 
-        Ok(Brick { graph, prefixes })
+        instances.sort();
+        instances.dedup();
+
+        Ok(instances)
     }
 
     pub fn sub_classes_of(&self, curie: &Curie) -> Result<Vec<Curie>> {
@@ -53,6 +177,95 @@ impl Brick {
             .collect()
     }
 
+    /// The class an alias stands for, following `brick:aliasOf`. Classes declared
+    /// `owl:equivalentClass` without being aliases stand for the first of them by name.
+    /// Any other class is its own canonical class.
+    pub fn canonical_class(&self, curie: &Curie) -> Result<Curie> {
+        let mut canonical = curie.clone();
+        let mut seen = vec![curie.clone()];
+
+        while let Some(target) = self
+            .object_curies(
+                self.get_ns(&canonical.prefix)?.get(&canonical.local_name)?,
+                self.get_ns("brick")?.get("aliasOf")?,
+            )?
+            .into_iter()
+            .next()
+        {
+            if seen.contains(&target) {
+                break;
+            }
+            seen.push(target.clone());
+            canonical = target;
+        }
+
+        let alias_of = self.get_ns("brick")?.get("aliasOf")?;
+        for equivalent in self.equivalent_classes_of(&canonical)? {
+            if equivalent < canonical
+                && self
+                    .graph
+                    .triples_matching(
+                        [self
+                            .get_ns(&equivalent.prefix)?
+                            .get(&equivalent.local_name)?],
+                        [alias_of],
+                        Any,
+                    )
+                    .next()
+                    .is_none()
+            {
+                canonical = equivalent;
+            }
+        }
+
+        Ok(canonical)
+    }
+
+    /// The aliases of the canonical class, sorted.
+    pub fn aliases_of(&self, curie: &Curie) -> Result<Vec<Curie>> {
+        let canonical = self.canonical_class(curie)?;
+        let alias_of = self.get_ns("brick")?.get("aliasOf")?;
+
+        let mut aliases = Vec::new();
+        let mut queue = VecDeque::from([canonical.clone()]);
+
+        while let Some(cur) = queue.pop_front() {
+            let class = self.get_ns(&cur.prefix)?.get(&cur.local_name)?;
+            for triple in self.graph.triples_matching(Any, [alias_of], [class]) {
+                let alias = Curie::from_term(triple?.s(), &self.prefixes)?;
+                if alias != canonical && !aliases.iter().any(|known| known == &alias) {
+                    aliases.push(alias.clone());
+                    queue.push_back(alias);
+                }
+            }
+        }
+
+        aliases.sort();
+
+        Ok(aliases)
+    }
+
+    /// The classes declared equivalent with `owl:equivalentClass`, in either direction.
+    fn equivalent_classes_of(&self, curie: &Curie) -> Result<Vec<Curie>> {
+        let class = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+
+        let mut equivalents = self.object_curies(class, owl::equivalentClass)?;
+        for triple in self
+            .graph
+            .triples_matching(Any, [owl::equivalentClass], [class])
+        {
+            if let Ok(equivalent) = Curie::from_term(triple?.s(), &self.prefixes) {
+                equivalents.push(equivalent);
+            }
+        }
+
+        equivalents.retain(|equivalent| equivalent != curie);
+        equivalents.sort();
+        equivalents.dedup();
+
+        Ok(equivalents)
+    }
+
     pub fn super_classes_of(&self, curie: &Curie) -> Result<Vec<Curie>> {
         let class = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
 
@@ -62,6 +275,115 @@ impl Brick {
             .collect()
     }
 
+    /// All the classes that transitively derive from the given class, ordered by distance.
+    /// If `all_paths` is set, every derivation path is reported, not only a shortest one.
+    pub fn descendants_of(&self, curie: &Curie, all_paths: bool) -> Result<Vec<ClassRelative>> {
+        Ok(self.hierarchy.relatives(curie, false, all_paths))
+    }
+
+    /// All the classes the given class transitively derives from, ordered by distance.
+    /// If `all_paths` is set, every derivation path is reported, not only a shortest one.
+    pub fn ancestors_of(&self, curie: &Curie, all_paths: bool) -> Result<Vec<ClassRelative>> {
+        Ok(self.hierarchy.relatives(curie, true, all_paths))
+    }
+
+    /// Check if a class is the same as, or transitively derives from, another class.
+    pub fn is_subclass_of(&self, sub: &Curie, sup: &Curie) -> bool {
+        self.hierarchy.is_subclass_of(sub, sup)
+    }
+
+    /// The ancestors shared by both classes, closest first.
+    pub fn common_ancestors(&self, a: &Curie, b: &Curie) -> Vec<Curie> {
+        self.hierarchy.common_ancestors(&[a.clone(), b.clone()])
+    }
+
+    /// The closest ancestor shared by all the classes.
+    pub fn lowest_common_ancestor(&self, classes: &[Curie]) -> Option<Curie> {
+        self.hierarchy.lowest_common_ancestor(classes)
+    }
+
+    /// Number of `rdfs:subClassOf` steps between two classes through their closest
+    /// common ancestor, `None` if the classes are not related.
+    pub fn class_distance(&self, a: &Curie, b: &Curie) -> Option<u32> {
+        self.hierarchy.distance(a, b)
+    }
+
+    /// Build the tree of the subclasses of a class, down to `max_depth` levels if set.
+    /// With `all_parents`, a class is listed under every one of its parents, otherwise
+    /// only under the first parent reached from the root.
+    pub fn class_tree(
+        &self,
+        root: &Curie,
+        max_depth: Option<u32>,
+        all_parents: bool,
+    ) -> Result<ClassNode> {
+        let mut children = HashMap::<Curie, Vec<Curie>>::new();
+
+        if !all_parents {
+            let mut visited = HashSet::from([root.clone()]);
+            let mut queue = VecDeque::from([root.clone()]);
+
+            while let Some(class) = queue.pop_front() {
+                let mut sub_classes = self.sub_classes_of(&class)?;
+                sub_classes.sort();
+                sub_classes.retain(|sub_class| visited.insert(sub_class.clone()));
+
+                queue.extend(sub_classes.iter().cloned());
+                children.insert(class, sub_classes);
+            }
+        }
+
+        self.class_node(root, max_depth, &mut vec![], &|class| {
+            if all_parents {
+                let mut sub_classes = self.sub_classes_of(class)?;
+                sub_classes.sort();
+                Ok(sub_classes)
+            } else {
+                Ok(children.get(class).cloned().unwrap_or_default())
+            }
+        })
+    }
+
+    fn class_node(
+        &self,
+        curie: &Curie,
+        max_depth: Option<u32>,
+        path: &mut Vec<Curie>,
+        children_of: &dyn Fn(&Curie) -> Result<Vec<Curie>>,
+    ) -> Result<ClassNode> {
+        let class = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+
+        let deprecated = self.deprecation_of(curie)?.is_some();
+
+        let tag_count = self
+            .graph
+            .triples_matching(
+                [class],
+                [self.get_ns("brick")?.get("hasAssociatedTag")?],
+                Any,
+            )
+            .count() as u32;
+
+        let mut children = Vec::new();
+        if max_depth.is_none_or(|depth| path.len() < depth as usize) {
+            path.push(curie.clone());
+            for child in children_of(curie)? {
+                if !path.contains(&child) {
+                    children.push(self.class_node(&child, max_depth, path, children_of)?);
+                }
+            }
+            path.pop();
+        }
+
+        Ok(ClassNode {
+            curie: curie.clone(),
+            label: self.literal_of(class, rdfs::label)?,
+            children,
+            deprecated,
+            tag_count,
+        })
+    }
+
     pub fn class_tags(&self, curie: &Curie) -> Result<Vec<String>> {
         let class = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
 
@@ -75,116 +397,473 @@ impl Brick {
             .collect()
     }
 
+    /// Search the classes by their name, label, definition, tags and aliases.
+    /// Query words match exactly, as a prefix or with a few typos.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        self.search.search(query, limit)
+    }
+
+    /// Suggest point classes for a raw point name such as `AHU1_SAT`, using the
+    /// built-in abbreviations dictionary. Best candidates first.
+    pub fn suggest_classes(&self, point_name: &str, limit: usize) -> Vec<ClassSuggestion> {
+        self.suggest_classes_with(point_name, &Abbreviations::new(), limit)
+    }
+
+    /// Suggest point classes for a raw point name, expanding its abbreviations
+    /// with the given dictionary.
+    pub fn suggest_classes_with(
+        &self,
+        point_name: &str,
+        abbreviations: &Abbreviations,
+        limit: usize,
+    ) -> Vec<ClassSuggestion> {
+        suggest_classes(
+            &self.tags,
+            &self.hierarchy,
+            |class| self.current_class(class),
+            point_name,
+            abbreviations,
+            limit,
+        )
+    }
+
+    /// The class to use in place of a class: its canonical class, or the class
+    /// replacing it when deprecated. `None` for deprecated classes without a
+    /// replacement.
+    fn current_class(&self, curie: &Curie) -> Option<Curie> {
+        let mut current = self.canonical_class(curie).ok()?;
+        let mut seen = vec![current.clone()];
+
+        while let Some(deprecation) = self.deprecation_of(&current).ok()? {
+            let replacement = self.canonical_class(&deprecation.replaced_by?).ok()?;
+            if seen.contains(&replacement) {
+                return None;
+            }
+            seen.push(replacement.clone());
+            current = replacement;
+        }
+
+        Some(current)
+    }
+
+    /// All the tags declared in the ontology, sorted by name.
+    pub fn tags(&self) -> Result<Vec<TagInfo>> {
+        self.instances_of(self.get_ns("brick")?.get("Tag")?)?
+            .iter()
+            .map(|tag| self.tag_info(tag))
+            .collect()
+    }
+
+    /// Describe a tag by its name, the name is matched case insensitively.
+    pub fn tag(&self, name: &str) -> Result<TagInfo> {
+        let tag = Curie::new("tag", self.tags.name(name).unwrap_or(name));
+        let tag_term = self.get_ns(&tag.prefix)?.get(&tag.local_name)?;
+
+        if self
+            .graph
+            .triples_matching([tag_term], Any, Any)
+            .next()
+            .is_none()
+        {
+            return Err(anyhow!("Unknown tag {}", name));
+        }
+
+        self.tag_info(&tag)
+    }
+
+    fn tag_info(&self, tag: &Curie) -> Result<TagInfo> {
+        let tag_term = self.get_ns(&tag.prefix)?.get(&tag.local_name)?;
+
+        Ok(TagInfo {
+            name: tag.local_name.clone(),
+            label: self.literal_of(tag_term, rdfs::label)?,
+            definition: self.literal_of(tag_term, self.get_ns("skos")?.get("definition")?)?,
+            class_count: self.tags.class_count(&tag.local_name),
+        })
+    }
+
+    /// Infer the classes described by a set of tags. Classes having exactly the given
+    /// tags are reported as exact matches, the ones sharing only some of them are ranked
+    /// by their precision and recall. Tags are matched case insensitively.
+    pub fn classes_for_tags(&self, tags: &[&str]) -> TagInference {
+        self.tags.classes_for_tags(tags)
+    }
+
     pub fn class_properties(&self, curie: &Curie) -> Result<Vec<BrickProperty>> {
         let class = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+
+        self.shape_properties(class, false)
+    }
+
+    /// Same as [`Brick::class_properties`], but also expands the property list
+    /// of every shape referenced by `sh:node`.
+    pub fn class_properties_expanded(&self, curie: &Curie) -> Result<Vec<BrickProperty>> {
+        let class = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+
+        self.shape_properties(class, true)
+    }
+
+    /// Compute the properties of a class including the ones inherited from all of its
+    /// superclasses. When a property with the same path is declared along the chain,
+    /// the closest declaration wins, tightened by the constraints of the ancestors.
+    pub fn effective_properties(&self, curie: &Curie) -> Result<Vec<InheritedProperty>> {
+        let mut effective = Vec::<InheritedProperty>::new();
+
+        let mut visited = HashSet::from([curie.clone()]);
+        let mut queue = VecDeque::from([curie.clone()]);
+
+        while let Some(class) = queue.pop_front() {
+            for prop in self.class_properties(&class)? {
+                // Only declarations from other classes override, shapes of the same
+                // class sharing a path stay separate.
+                let mut overridden = false;
+                for inherited in effective.iter_mut().filter(|inherited| {
+                    inherited.defined_in != class && same_property(&inherited.property, &prop)
+                }) {
+                    merge_ancestor_property(&mut inherited.property, &prop);
+
+                    if !inherited.overrides.contains(&class) {
+                        inherited.overrides.push(class.clone());
+                    }
+                    overridden = true;
+                }
+
+                if !overridden {
+                    effective.push(InheritedProperty {
+                        property: prop,
+                        defined_in: class.clone(),
+                        overrides: Vec::new(),
+                    });
+                }
+            }
+
+            for super_class in self.super_classes_of(&class)? {
+                if visited.insert(super_class.clone()) {
+                    queue.push_back(super_class);
+                }
+            }
+        }
+
+        Ok(effective)
+    }
+
+    /// Describe any SHACL node shape, whether or not it is also a class.
+    /// Fails when the CURIE is neither a `sh:NodeShape` nor declares targets.
+    pub fn shape(&self, curie: &Curie) -> Result<NodeShape> {
+        let shape = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+        let sh = self.get_ns("sh")?;
+
+        let mut is_shape = self.has_type(shape, sh.get("NodeShape")?)?;
+        for predicate in ["targetClass", "targetSubjectsOf", "targetObjectsOf"] {
+            is_shape = is_shape
+                || self
+                    .graph
+                    .triples_matching([shape], [sh.get(predicate)?], Any)
+                    .next()
+                    .is_some();
+        }
+
+        if !is_shape {
+            return Err(anyhow!("Unknown shape {curie}"));
+        }
+
+        let severity = self
+            .object_curies(shape, sh.get("severity")?)?
+            .into_iter()
+            .next();
+
+        let message = self
+            .graph
+            .triples_matching([&shape], [sh.get("message")?], Any)
+            .next()
+            .transpose()?
+            .and_then(|tr| tr.o().lexical_form().map(|v| v.to_string()))
+            .unwrap_or_default();
+
+        let closed = self
+            .graph
+            .triples_matching([&shape], [sh.get("closed")?], Any)
+            .next()
+            .transpose()?
+            .map(|tr| parse_literal(tr.o()))
+            .transpose()?
+            .unwrap_or_default();
+
+        let ignored_properties = self
+            .graph
+            .triples_matching([&shape], [sh.get("ignoredProperties")?], Any)
+            .next()
+            .transpose()?
+            .map(|tr| self.collect_list(tr.o().clone()))
+            .transpose()?
+            .unwrap_or_default()
+            .iter()
+            .map(|term| Curie::from_term(term, &self.prefixes))
+            .collect::<Result<Vec<Curie>>>()?;
+
+        Ok(NodeShape {
+            shape: curie.clone(),
+            target_classes: self.object_curies(shape, sh.get("targetClass")?)?,
+            target_subjects_of: self.object_curies(shape, sh.get("targetSubjectsOf")?)?,
+            target_objects_of: self.object_curies(shape, sh.get("targetObjectsOf")?)?,
+            severity,
+            message,
+            closed,
+            ignored_properties,
+            properties: self.shape_properties(shape, false)?,
+        })
+    }
+
+    /// Find all the named shapes that target the given class or property, either
+    /// explicitly or implicitly by being the class itself.
+    pub fn shapes_targeting(&self, curie: &Curie) -> Result<Vec<NodeShape>> {
+        let target = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+        let sh = self.get_ns("sh")?;
+
+        let mut shapes = Vec::<Curie>::new();
+
+        // SHACL implicit class targets: a node shape that is also a class.
+        if self.has_type(target, sh.get("NodeShape")?)?
+            && (self.has_type(target, rdfs::Class)?
+                || self.has_type(target, self.get_ns("owl")?.get("Class")?)?)
+        {
+            shapes.push(curie.clone());
+        }
+
+        for predicate in ["targetClass", "targetSubjectsOf", "targetObjectsOf"] {
+            for triple in self
+                .graph
+                .triples_matching(Any, [sh.get(predicate)?], [&target])
+            {
+                let triple = triple?;
+
+                if let Ok(shape) = Curie::from_term(triple.s(), &self.prefixes) {
+                    if !shapes.contains(&shape) {
+                        shapes.push(shape);
+                    }
+                }
+            }
+        }
+
+        shapes.iter().map(|shape| self.shape(shape)).collect()
+    }
+
+    /// Collect the properties of a shape. With `expand_nodes`, the properties of
+    /// the shapes referenced by `sh:node` are collected as well.
+    fn shape_properties<T: Term>(
+        &self,
+        shape: T,
+        expand_nodes: bool,
+    ) -> Result<Vec<BrickProperty>> {
+        let shape = shape.into_term::<SimpleTerm>();
+        let mut visited = vec![term_key(&shape)];
+
+        self.collect_shape_properties(&shape, expand_nodes, &mut visited)
+    }
+
+    /// Collect the properties of a shape, `visited` holds the shapes being collected.
+    fn collect_shape_properties(
+        &self,
+        shape: &SimpleTerm,
+        expand_nodes: bool,
+        visited: &mut Vec<String>,
+    ) -> Result<Vec<BrickProperty>> {
         let prop = self.get_ns("sh")?.get("property")?;
 
         let mut props = Vec::<BrickProperty>::new();
 
         for prop_term in self
             .graph
-            .triples_matching([&class], [&prop], Any)
+            .triples_matching([shape], [&prop], Any)
             .map(|triple| triple.map(|el| el.o().clone()))
         {
-            let prop_term = prop_term?;
-            self.collect_props(prop_term, &mut props)?;
+            props.push(self.collect_prop(prop_term?, expand_nodes, visited)?);
         }
 
         Ok(props)
     }
 
-    fn collect_props(
+    fn node_shape_ref(
+        &self,
+        node_term: SimpleTerm,
+        expand_nodes: bool,
+        visited: &mut Vec<String>,
+    ) -> Result<NodeShapeRef> {
+        let shape = Curie::from_term(&node_term, &self.prefixes).ok();
+
+        let key = term_key(&node_term);
+        if !expand_nodes || visited.contains(&key) {
+            return Ok(NodeShapeRef {
+                shape,
+                properties: Vec::new(),
+            });
+        }
+
+        visited.push(key);
+        let properties = self.collect_shape_properties(&node_term, expand_nodes, visited);
+        visited.pop();
+
+        Ok(NodeShapeRef {
+            shape,
+            properties: properties?,
+        })
+    }
+
+    /// Collect a property shape into a single property, including the member
+    /// shapes of its logical constraints and its qualified value shape.
+    fn collect_prop(
         &self,
         prop_term: SimpleTerm,
-        props: &mut Vec<BrickProperty>,
-    ) -> Result<(), anyhow::Error> {
-        let mut cur_prop = String::default();
+        expand_nodes: bool,
+        visited: &mut Vec<String>,
+    ) -> Result<BrickProperty> {
+        let mut prop = BrickProperty::default();
 
-        for triple in self.graph.triples_matching([prop_term.clone()], Any, Any) {
+        for triple in self.graph.triples_matching([&prop_term], Any, Any) {
             let triple = triple?;
 
-            if triple.s().bnode_id().is_some_and(|val| **val != cur_prop) {
-                props.push(BrickProperty::default());
-                cur_prop = prop_term
-                    .bnode_id()
-                    .ok_or_else(|| anyhow!("Expecting Blank Node"))?
-                    .to_string();
-            }
+            let val = triple.p().iri().ok_or_else(|| anyhow!("Expecting IRI"))?;
+            let base = val.as_base();
+            let fragment = base.fragment().ok_or_else(|| anyhow!("Missing fragment"))?;
 
-            if let Some(prop) = props.last_mut() {
-                let val = triple.p().iri().ok_or_else(|| anyhow!("Expecting IRI"))?;
-                let base = val.as_base();
-                let fragment = base.fragment().ok_or_else(|| anyhow!("Missing fragment"))?;
-
-                if fragment == "message" {
-                    prop.definition = triple
+            if fragment == "message" {
+                prop.definition = triple
+                    .o()
+                    .lexical_form()
+                    .ok_or_else(|| anyhow!("Expecting literal"))?
+                    .to_string();
+            } else if fragment == "class" {
+                prop.class = Curie::from_term(triple.o(), &self.prefixes)?;
+            } else if fragment == "datatype" {
+                let iri = triple.o().iri().ok_or_else(|| anyhow!("Expecting IRI"))?;
+                let curie = Curie::from_iri(iri, &self.prefixes)?;
+
+                prop.datatype = Some(curie);
+            } else if fragment == "nodeKind" {
+                let iri = triple.o().iri().ok_or_else(|| anyhow!("Expecting IRI"))?;
+                let curie = Curie::from_iri(iri, &self.prefixes)?;
+
+                prop.node_kind = Some(curie);
+            } else if fragment == "path" {
+                prop.path = self.path_value(triple.o())?;
+            } else if fragment == "minCount" {
+                prop.min_count = Some(parse_literal(triple.o())?);
+            } else if fragment == "maxCount" {
+                prop.max_count = Some(parse_literal(triple.o())?);
+            } else if fragment == "minLength" {
+                prop.min_length = Some(parse_literal(triple.o())?);
+            } else if fragment == "maxLength" {
+                prop.max_length = Some(parse_literal(triple.o())?);
+            } else if fragment == "minInclusive" {
+                prop.min_inclusive = Some(parse_literal(triple.o())?);
+            } else if fragment == "maxInclusive" {
+                prop.max_inclusive = Some(parse_literal(triple.o())?);
+            } else if fragment == "minExclusive" {
+                prop.min_exclusive = Some(parse_literal(triple.o())?);
+            } else if fragment == "maxExclusive" {
+                prop.max_exclusive = Some(parse_literal(triple.o())?);
+            } else if fragment == "pattern" {
+                prop.pattern = Some(
+                    triple
                         .o()
                         .lexical_form()
                         .ok_or_else(|| anyhow!("Expecting literal"))?
-                        .to_string();
-                } else if fragment == "class" {
-                    prop.class = Curie::from_term(triple.o(), &self.prefixes)?;
-                } else if fragment == "datatype" {
-                    let iri = triple.o().iri().ok_or_else(|| anyhow!("Expecting IRI"))?;
-                    let curie = Curie::from_iri(iri, &self.prefixes)?;
-
-                    prop.datatype = Some(curie);
-                } else if fragment == "nodeKind" {
-                    let iri = triple.o().iri().ok_or_else(|| anyhow!("Expecting IRI"))?;
-                    let curie = Curie::from_iri(iri, &self.prefixes)?;
-
-                    prop.node_kind = Some(curie);
-                } else if fragment == "path" {
-                    prop.path = without_prefix(triple.o())?;
-                } else if fragment == "not" {
-                    prop.logical_constraints
-                        .push(self.get_not_constraint(triple.o().clone())?);
-                } else if fragment == "and" {
-                    prop.logical_constraints
-                        .push(self.get_and_constraint(triple.o().clone())?);
-                } else if fragment == "or" {
-                    prop.logical_constraints
-                        .push(self.get_or_constraint(triple.o().clone())?);
-                } else if fragment == "xone" {
-                    prop.logical_constraints
-                        .push(self.get_xone_constraint(triple.o().clone())?);
+                        .to_string(),
+                );
+            } else if fragment == "in" {
+                prop.one_of = self
+                    .collect_list(triple.o().clone())?
+                    .iter()
+                    .map(|term| self.term_value(term))
+                    .collect::<Result<Vec<String>>>()?;
+            } else if fragment == "hasValue" {
+                prop.has_value_of = self.term_value(triple.o())?;
+            } else if fragment == "equals" {
+                prop.constraints
+                    .push(PropertyPairConstraint::Equal(Curie::from_term(
+                        triple.o(),
+                        &self.prefixes,
+                    )?));
+            } else if fragment == "disjoint" {
+                prop.constraints
+                    .push(PropertyPairConstraint::Disjoint(Curie::from_term(
+                        triple.o(),
+                        &self.prefixes,
+                    )?));
+            } else if fragment == "lessThan" {
+                prop.constraints
+                    .push(PropertyPairConstraint::LessThan(Curie::from_term(
+                        triple.o(),
+                        &self.prefixes,
+                    )?));
+            } else if fragment == "lessThanOrEquals" {
+                prop.constraints
+                    .push(PropertyPairConstraint::LessThanOrEqual(Curie::from_term(
+                        triple.o(),
+                        &self.prefixes,
+                    )?));
+            } else if fragment == "node" {
+                prop.node = Some(self.node_shape_ref(triple.o().clone(), expand_nodes, visited)?);
+            } else if fragment == "qualifiedValueShape" {
+                let qualified = prop.qualified.get_or_insert_with(Default::default);
+                qualified.value_shape = Curie::from_term(triple.o(), &self.prefixes).ok();
+
+                let key = term_key(triple.o());
+                if !visited.contains(&key) {
+                    visited.push(key);
+                    let shape = self.collect_prop(triple.o().clone(), expand_nodes, visited);
+                    visited.pop();
+
+                    *qualified.shape = shape?;
                 }
+            } else if fragment == "qualifiedMinCount" {
+                prop.qualified
+                    .get_or_insert_with(Default::default)
+                    .min_count = Some(parse_literal(triple.o())?);
+            } else if fragment == "qualifiedMaxCount" {
+                prop.qualified
+                    .get_or_insert_with(Default::default)
+                    .max_count = Some(parse_literal(triple.o())?);
+            } else if fragment == "qualifiedValueShapesDisjoint" {
+                prop.qualified.get_or_insert_with(Default::default).disjoint =
+                    parse_literal(triple.o())?;
+            } else if fragment == "not" {
+                prop.logical_constraints.push(self.get_not_constraint(
+                    triple.o().clone(),
+                    expand_nodes,
+                    visited,
+                )?);
+            } else if fragment == "and" {
+                prop.logical_constraints.push(self.get_and_constraint(
+                    triple.o().clone(),
+                    expand_nodes,
+                    visited,
+                )?);
+            } else if fragment == "or" {
+                prop.logical_constraints.push(self.get_or_constraint(
+                    triple.o().clone(),
+                    expand_nodes,
+                    visited,
+                )?);
+            } else if fragment == "xone" {
+                prop.logical_constraints.push(self.get_xone_constraint(
+                    triple.o().clone(),
+                    expand_nodes,
+                    visited,
+                )?);
             }
         }
 
-        Ok(())
+        Ok(prop)
     }
 
+    /// Describe a class. Aliases are resolved to their canonical class.
     pub fn class_desc(&self, curie: &Curie) -> Result<BrickEntity> {
+        let curie = &self.canonical_class(curie)?;
         let class = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
 
-        let label = self
-            .graph
-            .triples_matching([&class], [&rdfs::label], Any)
-            .map(|triple| {
-                triple
-                    .map(|tr| {
-                        tr.o()
-                            .lexical_form()
-                            .map_or(String::new(), |v| v.to_string())
-                    })
-                    .map_err(Into::into)
-            })
-            .collect::<Result<String>>()?;
-
-        let definition = self
-            .graph
-            .triples_matching([&class], [&self.get_ns("skos")?.get("definition")?], Any)
-            .map(|triple| {
-                triple
-                    .map(|tr| {
-                        tr.o()
-                            .lexical_form()
-                            .map_or(String::new(), |v| v.to_string())
-                    })
-                    .map_err(Into::into)
-            })
-            .collect::<Result<String>>()?;
+        let label = self.preferred_literal(class, rdfs::label)?;
+        let definition = self.literal_of(class, self.get_ns("skos")?.get("definition")?)?;
 
         let types = self
             .graph
@@ -196,6 +875,12 @@ impl Brick {
         let tags = self.class_tags(curie)?;
 
         let properties = self.class_properties(curie)?;
+        let deprecation = self.deprecation_of(curie)?;
+        let aliases = self.aliases_of(curie)?;
+        let equivalent_classes = self.equivalent_classes_of(curie)?;
+        let brick = self.get_ns("brick")?;
+        let substances = self.linked_curies(class, brick.get("hasSubstance")?, false)?;
+        let quantities = self.linked_curies(class, brick.get("hasQuantity")?, false)?;
 
         Ok(BrickEntity {
             name: curie.local_name.to_string(),
@@ -206,191 +891,2152 @@ impl Brick {
             super_classes,
             tags,
             properties,
+            deprecation,
+            aliases,
+            equivalent_classes,
+            substances,
+            quantities,
         })
     }
 
-    fn get_not_constraint(&self, or_term: SimpleTerm) -> Result<LogicalConstraint> {
-        let props = self.collect_prop_list(or_term)?;
-        Ok(LogicalConstraint::Not(props))
-    }
+    /// The point classes measuring a substance and a quantity, such as `brick:Supply_Air`
+    /// and `quantitykind:Temperature`. Narrower substances and quantities, and the ones
+    /// declared the same with `owl:sameAs`, match too. Aliases are left out.
+    pub fn points_for(
+        &self,
+        substance: Option<&Curie>,
+        quantity: Option<&Curie>,
+    ) -> Result<Vec<Curie>> {
+        let brick = self.get_ns("brick")?;
+        let alias_of = brick.get("aliasOf")?;
+
+        let substances = substance
+            .map(|substance| self.narrower_concepts(substance))
+            .transpose()?;
+        let quantities = quantity
+            .map(|quantity| self.narrower_concepts(quantity))
+            .transpose()?;
+
+        let point = Curie::new("brick", "Point");
+        let mut points = Vec::new();
+
+        for class in self.classes()? {
+            if !self.hierarchy.is_subclass_of(&class, &point) {
+                continue;
+            }
 
-    fn get_and_constraint(&self, or_term: SimpleTerm) -> Result<LogicalConstraint> {
-        let props = self.collect_prop_list(or_term)?;
-        Ok(LogicalConstraint::And(props))
-    }
+            let term = self.get_ns(&class.prefix)?.get(&class.local_name)?;
 
-    fn get_or_constraint(&self, or_term: SimpleTerm) -> Result<LogicalConstraint> {
-        let props = self.collect_prop_list(or_term)?;
-        Ok(LogicalConstraint::Or(props))
-    }
+            if self
+                .graph
+                .triples_matching([term], [alias_of], Any)
+                .next()
+                .is_some()
+            {
+                continue;
+            }
 
-    fn get_xone_constraint(&self, or_term: SimpleTerm) -> Result<LogicalConstraint> {
-        let props = self.collect_prop_list(or_term)?;
-        Ok(LogicalConstraint::XOne(props))
-    }
+            let matches = |expected: &Option<Vec<Curie>>, predicate: &str| -> Result<bool> {
+                let Some(expected) = expected else {
+                    return Ok(true);
+                };
 
-    fn collect_prop_list(
-        &self,
-        mut list_term: SimpleTerm,
-    ) -> Result<Vec<BrickProperty>, anyhow::Error> {
-        let mut props = Vec::<BrickProperty>::new();
-        'outer: loop {
-            for list_triple in self.graph.triples_matching([&list_term.clone()], Any, Any) {
-                let list_triple = list_triple?;
-
-                if list_triple
-                    .p()
-                    .iri()
-                    .is_some_and(|iri| iri.ends_with("#rest"))
-                {
-                    list_term = list_triple.o().to_owned();
-                    continue 'outer;
-                }
-                if list_triple
-                    .p()
-                    .iri()
-                    .is_some_and(|iri| iri.ends_with("#first"))
-                {
-                    for prop_triple in self.graph.triples_matching([list_triple.o()], Any, Any) {
-                        let prop_triple = prop_triple?;
-                        self.collect_props(prop_triple.s().to_owned(), &mut props)?;
-                    }
-                } else {
-                    break 'outer;
-                }
+                Ok(self
+                    .linked_curies(term, brick.get(predicate)?, false)?
+                    .iter()
+                    .any(|declared| expected.contains(declared)))
+            };
+
+            if matches(&substances, "hasSubstance")? && matches(&quantities, "hasQuantity")? {
+                points.push(class);
             }
-            break;
         }
-        Ok(props)
-    }
 
-    fn get_ns(&self, prefix: &str) -> Result<&Namespace<String>> {
-        self.prefixes
-            .get_ns(prefix)
-            .ok_or_else(|| anyhow!("Missing prefix {}", prefix))
+        Ok(points)
     }
-}
 
-fn without_prefix(term: &SimpleTerm) -> Result<String> {
-    let val = term.iri().ok_or_else(|| anyhow!("Expecting IRI"))?;
-    let base = val.as_base();
-    let fragment = base.fragment().ok_or_else(|| anyhow!("Missing fragment"))?;
+    /// A concept with all of its transitively narrower concepts, following
+    /// `skos:narrower`, inverse `skos:broader` and `owl:sameAs`.
+    fn narrower_concepts(&self, curie: &Curie) -> Result<Vec<Curie>> {
+        let skos = self.get_ns("skos")?;
 
-    Ok(fragment.to_string())
-}
+        let mut concepts = vec![curie.clone()];
+        let mut queue = VecDeque::from([curie.clone()]);
 
-fn only_prefix(term: &SimpleTerm) -> String {
-    let val = term.iri().unwrap();
+        while let Some(cur) = queue.pop_front() {
+            let term = self.get_ns(&cur.prefix)?.get(&cur.local_name)?;
 
-    let idx = val.rfind('#').map(|v| v + 1).unwrap_or_default();
-    let begin = val[..idx].rfind('/').map(|v| v + 1).unwrap_or(idx);
+            let related = [
+                self.linked_curies(term, skos.get("narrower")?, false)?,
+                self.linked_curies(term, skos.get("broader")?, true)?,
+                self.linked_curies(term, owl::sameAs, false)?,
+                self.linked_curies(term, owl::sameAs, true)?,
+            ];
 
-    val[begin..].to_string()
-}
+            for concept in related.into_iter().flatten() {
+                if !concepts.contains(&concept) {
+                    concepts.push(concept.clone());
+                    queue.push_back(concept);
+                }
+            }
+        }
+
+        Ok(concepts)
+    }
+
+    /// The quantity measured by a point class, declared with `brick:hasQuantity`
+    /// on the class or on its closest ancestor.
+    pub fn quantity_of(&self, curie: &Curie) -> Result<Option<QuantityKind>> {
+        let has_quantity = self.get_ns("brick")?.get("hasQuantity")?;
+
+        let classes = std::iter::once(curie.clone()).chain(
+            self.ancestors_of(curie, false)?
+                .into_iter()
+                .map(|relative| relative.class),
+        );
+
+        for class in classes {
+            let class = self.get_ns(&class.prefix)?.get(&class.local_name)?;
+
+            if let Some(quantity) = self
+                .linked_curies(class, has_quantity, false)?
+                .into_iter()
+                .next()
+            {
+                return self.quantity_kind(&quantity).map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Describe a quantity kind from the loaded graph. Its units are the ones it
+    /// lists with `qudt:applicableUnit` and the `qudt:Unit`s declaring it with `qudt:hasQuantityKind`.
+    pub fn quantity_kind(&self, curie: &Curie) -> Result<QuantityKind> {
+        let quantity = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+        let qudt = self.get_ns("qudt")?;
+
+        let mut applicable_units =
+            self.linked_curies(quantity, qudt.get("applicableUnit")?, false)?;
+        for unit in self.linked_curies(quantity, qudt.get("hasQuantityKind")?, true)? {
+            let term = self.get_ns(&unit.prefix)?.get(&unit.local_name)?;
+            if self
+                .graph
+                .triples_matching([term], [rdf::type_], [qudt.get("Unit")?])
+                .next()
+                .is_some()
+            {
+                applicable_units.push(unit);
+            }
+        }
+        applicable_units.sort();
+        applicable_units.dedup();
+
+        Ok(QuantityKind {
+            curie: curie.clone(),
+            label: self.preferred_literal(quantity, rdfs::label)?,
+            definition: self
+                .preferred_literal(quantity, self.get_ns("skos")?.get("definition")?)?,
+            dimension_vector: self.quantity_dimension(curie, &mut vec![])?,
+            applicable_units,
+            broader: self.linked_curies(quantity, self.get_ns("skos")?.get("broader")?, false)?,
+        })
+    }
+
+    /// Describe a unit from the loaded graph.
+    pub fn unit(&self, curie: &Curie) -> Result<Unit> {
+        let unit = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+        let qudt = self.get_ns("qudt")?;
+
+        let number = |name: &str| -> Result<Option<f64>> {
+            Ok(self
+                .graph
+                .triples_matching([unit], [qudt.get(name)?], Any)
+                .next()
+                .transpose()?
+                .and_then(|tr| parse_literal(tr.o()).ok()))
+        };
+
+        Ok(Unit {
+            curie: curie.clone(),
+            label: self.preferred_literal(unit, rdfs::label)?,
+            symbol: self.preferred_literal(unit, qudt.get("symbol")?)?,
+            dimension_vector: self.dimension_vector_of(unit)?,
+            conversion_multiplier: number("conversionMultiplier")?,
+            conversion_offset: number("conversionOffset")?,
+            quantity_kinds: self.linked_curies(unit, qudt.get("hasQuantityKind")?, false)?,
+        })
+    }
+
+    /// Convert a value between two units of the same dimension, using their
+    /// QUDT conversion multipliers and offsets.
+    pub fn convert(&self, value: f64, from_unit: &Curie, to_unit: &Curie) -> Result<f64> {
+        qudt::convert(value, &self.unit(from_unit)?, &self.unit(to_unit)?)
+    }
+
+    /// The units applicable to a point class, through its quantity, or to a quantity kind.
+    pub fn applicable_units(&self, curie: &Curie) -> Result<Vec<Curie>> {
+        Ok(self.quantity_or_self(curie)?.applicable_units)
+    }
+
+    /// Check if a unit can be used for a point class or a quantity kind: either it
+    /// is one of the applicable units, or it has the same dimension vector.
+    pub fn is_unit_applicable(&self, curie: &Curie, unit: &Curie) -> Result<bool> {
+        let quantity = self.quantity_or_self(curie)?;
+
+        if quantity.applicable_units.contains(unit) {
+            return Ok(true);
+        }
+
+        Ok(
+            match (quantity.dimension_vector, self.unit(unit)?.dimension_vector) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
+        )
+    }
+
+    fn quantity_or_self(&self, curie: &Curie) -> Result<QuantityKind> {
+        match self.quantity_of(curie)? {
+            Some(quantity) => Ok(quantity),
+            None => self.quantity_kind(curie),
+        }
+    }
+
+    fn quantity_dimension(
+        &self,
+        curie: &Curie,
+        visited: &mut Vec<Curie>,
+    ) -> Result<Option<DimensionVector>> {
+        if visited.contains(curie) {
+            return Ok(None);
+        }
+        visited.push(curie.clone());
+
+        let quantity = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+
+        if let Some(vector) = self.dimension_vector_of(quantity)? {
+            return Ok(Some(vector));
+        }
+
+        for broader in self.linked_curies(quantity, self.get_ns("skos")?.get("broader")?, false)? {
+            if let Some(vector) = self.quantity_dimension(&broader, visited)? {
+                return Ok(Some(vector));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn dimension_vector_of<T: Term>(&self, term: T) -> Result<Option<DimensionVector>> {
+        let Some(triple) = self
+            .graph
+            .triples_matching(
+                [term],
+                [self.get_ns("qudt")?.get("hasDimensionVector")?],
+                Any,
+            )
+            .next()
+            .transpose()?
+        else {
+            return Ok(None);
+        };
+
+        local_name(triple.o())?.parse().map(Some)
+    }
+
+    /// The English or untagged literal value of a subject property, or else the first one.
+    fn preferred_literal<S: Term, P: Term>(&self, subject: S, predicate: P) -> Result<String> {
+        let mut value = None;
+
+        for triple in self.graph.triples_matching([subject], [predicate], Any) {
+            let triple = triple?;
+            let Some(lexical) = triple.o().lexical_form() else {
+                continue;
+            };
+
+            match triple.o().language_tag() {
+                Some(tag) if tag.as_str() == "en" => return Ok(lexical.to_string()),
+                None => return Ok(lexical.to_string()),
+                _ => {
+                    value.get_or_insert_with(|| lexical.to_string());
+                }
+            }
+        }
+
+        Ok(value.unwrap_or_default())
+    }
+
+    /// Describe the values expected by an entity property: datatypes, units,
+    /// quantity kind, enumerated values and limits, read from the property shapes
+    /// of its value shape and of the node shapes it requires with `sh:node`.
+    pub fn entity_property_desc(&self, curie: &Curie) -> Result<EntityPropertyDesc> {
+        let property = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+
+        if self
+            .graph
+            .triples_matching([property], Any, Any)
+            .next()
+            .is_none()
+        {
+            return Err(anyhow!("Unknown entity property {curie}"));
+        }
+
+        let mut desc = EntityPropertyDesc {
+            property: curie.clone(),
+            label: self.literal_of(property, rdfs::label)?,
+            definition: self.literal_of(property, self.get_ns("skos")?.get("definition")?)?,
+            value_shape: self.value_shape_of(curie, &mut vec![])?,
+            ..Default::default()
+        };
+
+        if let Some(value_shape) = &desc.value_shape {
+            for shape in self.node_shapes(value_shape)? {
+                let shape = self.get_ns(&shape.prefix)?.get(&shape.local_name)?;
+
+                for prop in self.shape_properties(shape, false)? {
+                    match prop.path.as_str() {
+                        "value" => {
+                            desc.datatypes.extend(prop.datatype.clone());
+                            for constraint in &prop.logical_constraints {
+                                if let LogicalConstraint::Or(alternatives) = constraint {
+                                    desc.datatypes.extend(
+                                        alternatives.iter().filter_map(|alt| alt.datatype.clone()),
+                                    );
+                                }
+                            }
+
+                            for value in prop.one_of {
+                                if !desc.enumeration.contains(&value) {
+                                    desc.enumeration.push(value);
+                                }
+                            }
+
+                            desc.min_inclusive = desc.min_inclusive.or(prop.min_inclusive);
+                            desc.max_inclusive = desc.max_inclusive.or(prop.max_inclusive);
+                            desc.min_exclusive = desc.min_exclusive.or(prop.min_exclusive);
+                            desc.max_exclusive = desc.max_exclusive.or(prop.max_exclusive);
+                        }
+                        "hasUnit" => {
+                            desc.units.extend(
+                                prop.one_of
+                                    .iter()
+                                    .chain(Some(&prop.has_value_of))
+                                    .filter_map(|unit| Curie::try_from(unit.as_str()).ok()),
+                            );
+                        }
+                        "hasQuantityKind" | "hasQuantity" => {
+                            desc.quantity_kind = desc
+                                .quantity_kind
+                                .or_else(|| Curie::try_from(prop.has_value_of.as_str()).ok());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        desc.units.sort();
+        desc.units.dedup();
+
+        let mut datatypes = Vec::<Curie>::new();
+        for datatype in desc.datatypes.drain(..) {
+            if !datatypes.contains(&datatype) {
+                datatypes.push(datatype);
+            }
+        }
+        desc.datatypes = datatypes;
+
+        if desc.quantity_kind.is_none() {
+            desc.quantity_kind = self.quantity_for_units(&desc.units)?;
+        }
+
+        Ok(desc)
+    }
+
+    /// A node shape followed by the node shapes it requires with `sh:node`, transitively.
+    fn node_shapes(&self, shape: &Curie) -> Result<Vec<Curie>> {
+        let node = self.get_ns("sh")?.get("node")?;
+
+        let mut shapes = vec![shape.clone()];
+        let mut i = 0;
+        while let Some(shape) = shapes.get(i) {
+            let term = self.get_ns(&shape.prefix)?.get(&shape.local_name)?;
+            for required in self.linked_curies(term, node, false)? {
+                if !shapes.contains(&required) {
+                    shapes.push(required);
+                }
+            }
+            i += 1;
+        }
+
+        Ok(shapes)
+    }
+
+    /// The node shape of an entity property values, from the `sh:node` of the
+    /// property shapes with that path, or else of its super properties.
+    fn value_shape_of(&self, curie: &Curie, visited: &mut Vec<Curie>) -> Result<Option<Curie>> {
+        if visited.contains(curie) {
+            return Ok(None);
+        }
+        visited.push(curie.clone());
+
+        let property = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+        let sh = self.get_ns("sh")?;
+
+        for triple in self
+            .graph
+            .triples_matching(Any, [sh.get("path")?], [property])
+        {
+            let triple = triple?;
+            if let Some(shape) = self
+                .linked_curies(triple.s(), sh.get("node")?, false)?
+                .into_iter()
+                .next()
+            {
+                return Ok(Some(shape));
+            }
+        }
+
+        for super_property in self.linked_curies(property, rdfs::subPropertyOf, false)? {
+            if let Some(shape) = self.value_shape_of(&super_property, visited)? {
+                return Ok(Some(shape));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// The narrowest quantity whose `qudt:applicableUnit` list has all the units.
+    /// Only the quantities applicable to the first unit are considered.
+    fn quantity_for_units(&self, units: &[Curie]) -> Result<Option<Curie>> {
+        let Some(qudt) = self.prefixes.get_ns("qudt") else {
+            return Ok(None);
+        };
+        let applicable_unit = qudt.get("applicableUnit")?;
+
+        let mut terms = Vec::new();
+        for unit in units {
+            let Some(ns) = self.prefixes.get_ns(&unit.prefix) else {
+                return Ok(None);
+            };
+            terms.push(ns.get(&unit.local_name)?);
+        }
+
+        let Some((first, rest)) = terms.split_first() else {
+            return Ok(None);
+        };
+
+        let mut narrowest = None::<(usize, Curie)>;
+
+        for triple in self.graph.triples_matching(Any, [applicable_unit], [first]) {
+            let triple = triple?;
+            let quantity = triple.s();
+
+            if !rest.iter().all(|unit| {
+                self.graph
+                    .triples_matching([quantity], [applicable_unit], [unit])
+                    .next()
+                    .is_some()
+            }) {
+                continue;
+            }
+
+            let Ok(curie) = Curie::from_term(quantity, &self.prefixes) else {
+                continue;
+            };
+            let count = self
+                .graph
+                .triples_matching([quantity], [applicable_unit], Any)
+                .count();
+
+            if narrowest.as_ref().is_none_or(|(best_count, best)| {
+                Ord::cmp(&count, best_count)
+                    .then_with(|| Ord::cmp(&curie, best))
+                    .is_lt()
+            }) {
+                narrowest = Some((count, curie));
+            }
+        }
+
+        Ok(narrowest.map(|(_, quantity)| quantity))
+    }
+
+    /// The inverse of a relationship, from `owl:inverseOf` declared in either direction.
+    /// Unknown relationships have no inverse.
+    fn inverse_of(&self, curie: &Curie) -> Result<Option<Curie>> {
+        let property = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+
+        Ok(self
+            .linked_curies(property, owl::inverseOf, false)?
+            .into_iter()
+            .chain(self.linked_curies(property, owl::inverseOf, true)?)
+            .next())
+    }
+
+    /// Describe a relationship, with its inverse, domain, range, property
+    /// hierarchy and OWL characteristics.
+    pub fn relationship_desc(&self, curie: &Curie) -> Result<BrickRelationship> {
+        let property = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+
+        if self
+            .graph
+            .triples_matching([property], Any, Any)
+            .next()
+            .is_none()
+        {
+            return Err(anyhow!("Unknown relationship {curie}"));
+        }
+
+        let mut characteristics = self
+            .linked_curies(property, rdf::type_, false)?
+            .into_iter()
+            .filter(|ty| ty.prefix == "owl")
+            .filter_map(|ty| PropertyCharacteristic::from_owl_type(&ty.local_name))
+            .collect::<Vec<PropertyCharacteristic>>();
+        characteristics.sort();
+
+        Ok(BrickRelationship {
+            name: curie.local_name.to_string(),
+            namespace: curie.prefix.to_string(),
+            label: self.literal_of(property, rdfs::label)?,
+            definition: self.literal_of(property, self.get_ns("skos")?.get("definition")?)?,
+            inverse: self.inverse_of(curie)?,
+            domain: self.linked_curies(property, rdfs::domain, false)?,
+            range: self.linked_curies(property, rdfs::range, false)?,
+            super_properties: self.linked_curies(property, rdfs::subPropertyOf, false)?,
+            sub_properties: self.linked_curies(property, rdfs::subPropertyOf, true)?,
+            equivalent_properties: self.linked_curies(property, owl::equivalentProperty, false)?,
+            characteristics,
+        })
+    }
+
+    /// The relationships allowed from a subject class to an object class.
+    ///
+    /// A relationship is allowed when a property shape of the subject class, or of
+    /// one of its ancestors, constrains its objects to a class the object derives from.
+    /// Shapes of the object class allow the inverse relationships, and the relationships
+    /// not covered by shapes are checked against their domain and range. Classes excluded
+    /// with `sh:not [ sh:class ... ]` are honoured, other `sh:not` shapes and `sh:in`
+    /// value lists are not checked.
+    pub fn allowed_relationships(
+        &self,
+        subject: &Curie,
+        object: &Curie,
+    ) -> Result<Vec<AllowedRelationship>> {
+        let subject = self.canonical_class(subject)?;
+        let object = self.canonical_class(object)?;
+
+        let mut allowed = Vec::<AllowedRelationship>::new();
+
+        for (from, to, from_inverse) in [(&subject, &object, false), (&object, &subject, true)] {
+            let classes = std::iter::once(from.clone()).chain(
+                self.ancestors_of(from, false)?
+                    .into_iter()
+                    .map(|relative| relative.class),
+            );
+
+            for class in classes {
+                let shape = self.get_ns(&class.prefix)?.get(&class.local_name)?;
+                let sh = self.get_ns("sh")?;
+
+                for triple in self
+                    .graph
+                    .triples_matching([shape], [sh.get("property")?], Any)
+                {
+                    let prop = triple?.o().clone();
+
+                    let Some(path) = self
+                        .linked_curies(&prop, sh.get("path")?, false)?
+                        .into_iter()
+                        .next()
+                    else {
+                        continue;
+                    };
+
+                    if !self
+                        .permitted_classes(&prop)?
+                        .iter()
+                        .any(|class| self.hierarchy.is_subclass_of(to, class))
+                        || self
+                            .excluded_classes(&prop)?
+                            .iter()
+                            .any(|class| self.hierarchy.is_subclass_of(to, class))
+                    {
+                        continue;
+                    }
+
+                    let relationship = if from_inverse {
+                        match self.inverse_of(&path)? {
+                            Some(inverse) => inverse,
+                            None => continue,
+                        }
+                    } else {
+                        path
+                    };
+
+                    if allowed.iter().any(|a| a.relationship == relationship) {
+                        continue;
+                    }
+
+                    allowed.push(AllowedRelationship {
+                        relationship,
+                        shape: Some(class.clone()),
+                        property_shape: Curie::from_term(&prop, &self.prefixes).ok(),
+                        from_inverse,
+                        message: self.literal_of(&prop, sh.get("message")?)?,
+                    });
+                }
+            }
+        }
+
+        for relationship in self
+            .domain_ranges
+            .allowed(&self.hierarchy, &subject, &object)
+        {
+            if !allowed.iter().any(|a| &a.relationship == relationship) {
+                allowed.push(AllowedRelationship {
+                    relationship: relationship.clone(),
+                    ..Default::default()
+                });
+            }
+        }
+
+        allowed.sort_by(|a, b| Ord::cmp(&a.relationship, &b.relationship));
+
+        Ok(allowed)
+    }
+
+    /// The classes a property shape excludes for its values, from the `sh:class`
+    /// of its `sh:not` shapes.
+    fn excluded_classes(&self, prop: &SimpleTerm) -> Result<Vec<Curie>> {
+        let sh = self.get_ns("sh")?;
+
+        let mut classes = Vec::new();
+        for triple in self.graph.triples_matching([prop], [sh.get("not")?], Any) {
+            classes.extend(self.linked_curies(triple?.o(), sh.get("class")?, false)?);
+        }
+
+        Ok(classes)
+    }
+
+    /// The classes a property shape allows for its values, from `sh:class`
+    /// or the `sh:class` alternatives of `sh:or`.
+    fn permitted_classes(&self, prop: &SimpleTerm) -> Result<Vec<Curie>> {
+        let sh = self.get_ns("sh")?;
+
+        let mut classes = self.linked_curies(prop, sh.get("class")?, false)?;
+
+        for triple in self.graph.triples_matching([prop], [sh.get("or")?], Any) {
+            for item in self.collect_list(triple?.o().clone())? {
+                classes.extend(self.linked_curies(&item, sh.get("class")?, false)?);
+            }
+        }
+
+        Ok(classes)
+    }
+
+    /// The IRIs linked to a term by a predicate, as objects or, when `incoming`, as subjects.
+    /// Blank nodes and IRIs outside the known namespaces are skipped.
+    fn linked_curies<T: Term, P: Term>(
+        &self,
+        term: T,
+        predicate: P,
+        incoming: bool,
+    ) -> Result<Vec<Curie>> {
+        let triples = if incoming {
+            self.graph
+                .triples_matching(Any, [predicate], [term])
+                .map(|triple| triple.map(|tr| tr.s().clone()))
+                .collect::<Result<Vec<SimpleTerm>, _>>()?
+        } else {
+            self.graph
+                .triples_matching([term], [predicate], Any)
+                .map(|triple| triple.map(|tr| tr.o().clone()))
+                .collect::<Result<Vec<SimpleTerm>, _>>()?
+        };
+
+        let mut curies = triples
+            .iter()
+            .filter_map(|term| Curie::from_term(term, &self.prefixes).ok())
+            .collect::<Vec<Curie>>();
+        curies.sort();
+        curies.dedup();
+
+        Ok(curies)
+    }
+
+    /// All the deprecated classes, with their version, migration message and replacement.
+    /// Classes whose `owl:deprecated` value is not a boolean are listed too, with it.
+    pub fn deprecated_classes(&self) -> Result<Vec<DeprecatedClass>> {
+        let mut deprecated = Vec::new();
+
+        for class in self.classes()? {
+            if let Some(deprecation) = self.deprecation_of(&class)? {
+                deprecated.push(DeprecatedClass { class, deprecation });
+            }
+        }
+
+        Ok(deprecated)
+    }
+
+    fn deprecation_of(&self, curie: &Curie) -> Result<Option<Deprecation>> {
+        let class = self.get_ns(&curie.prefix)?.get(&curie.local_name)?;
+
+        // A malformed flag is reported as a deprecation, for it to be reviewed
+        // rather than silently missed.
+        let malformed_flag = match self
+            .graph
+            .triples_matching([class], [self.get_ns("owl")?.get("deprecated")?], Any)
+            .next()
+            .transpose()?
+        {
+            Some(triple) => match triple.o().lexical_form().as_deref() {
+                Some("true" | "1") => None,
+                Some("false" | "0") => return Ok(None),
+                lexical => Some(lexical.unwrap_or_default().to_string()),
+            },
+            None => return Ok(None),
+        };
+
+        let brick = self.get_ns("brick")?;
+
+        let mut deprecation = Deprecation {
+            version: self.literal_of(class, brick.get("deprecatedInVersion")?)?,
+            message: self.literal_of(class, brick.get("deprecationMitigationMessage")?)?,
+            replaced_by: self
+                .object_curies(class, brick.get("isReplacedBy")?)?
+                .into_iter()
+                .next(),
+            malformed_flag,
+        };
+
+        // Newer releases group the details under a `brick:deprecation` node.
+        let details = self
+            .graph
+            .triples_matching([class], [brick.get("deprecation")?], Any)
+            .next()
+            .transpose()?;
+        if let Some(details) = details {
+            let details = details.o();
+            if deprecation.version.is_empty() {
+                deprecation.version =
+                    self.literal_of(details, brick.get("deprecatedInVersion")?)?;
+            }
+            if deprecation.message.is_empty() {
+                deprecation.message =
+                    self.literal_of(details, brick.get("deprecationMitigationMessage")?)?;
+            }
+            if deprecation.replaced_by.is_none() {
+                deprecation.replaced_by = self
+                    .object_curies(details, brick.get("isReplacedBy")?)?
+                    .into_iter()
+                    .next();
+            }
+        }
+
+        Ok(Some(deprecation))
+    }
+
+    /// Migrate a Turtle model away from the deprecated classes and properties.
+    ///
+    /// Instances of deprecated classes are retyped to their `brick:isReplacedBy`
+    /// replacement, and the `brick:deprecationMitigationRule` rules renaming
+    /// properties are applied. The other rules are reported as warnings.
+    pub fn migrate_model(&self, model: &str) -> Result<MigrationReport> {
+        let classes = self
+            .deprecated_classes()?
+            .into_iter()
+            .map(|deprecated| (deprecated.class, deprecated.deprecation))
+            .collect::<HashMap<_, _>>();
+
+        let (renames, unsupported) = self.mitigation_rules()?;
+
+        migration::migrate(model, &self.prefixes, &classes, &renames, &unsupported)
+    }
+
+    /// The mitigation rules, split in property renames and the rules that
+    /// can't be applied automatically.
+    fn mitigation_rules(&self) -> Result<(Vec<PropertyRename>, Vec<UnsupportedRule>)> {
+        let brick = self.get_ns("brick")?;
+        let sh = self.get_ns("sh")?;
+
+        let mut renames = Vec::new();
+        let mut unsupported = Vec::new();
+
+        for triple in
+            self.graph
+                .triples_matching(Any, [brick.get("deprecationMitigationRule")?], Any)
+        {
+            let triple = triple?;
+            let message =
+                self.literal_of(triple.s(), brick.get("deprecationMitigationMessage")?)?;
+
+            // The rule hangs off the deprecated term, or off its `brick:deprecation` node.
+            let deprecated = if triple.s().is_blank_node() {
+                self.linked_curies(triple.s(), brick.get("deprecation")?, true)?
+                    .into_iter()
+                    .next()
+            } else {
+                Curie::from_term(triple.s(), &self.prefixes).ok()
+            };
+
+            for rule in self
+                .graph
+                .triples_matching([triple.o()], [sh.get("rule")?], Any)
+            {
+                let rule = rule?;
+                let construct = self.literal_of(rule.o(), sh.get("construct")?)?;
+
+                match (migration::parse_rename_rule(&construct), &deprecated) {
+                    (Some((from, to)), _) => renames.push(PropertyRename {
+                        from,
+                        to,
+                        message: message.clone(),
+                    }),
+                    (None, Some(deprecated)) => unsupported.push(UnsupportedRule {
+                        deprecated: deprecated.clone(),
+                        message: message.clone(),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+
+        Ok((renames, unsupported))
+    }
+
+    fn get_not_constraint(
+        &self,
+        or_term: SimpleTerm,
+        expand_nodes: bool,
+        visited: &mut Vec<String>,
+    ) -> Result<LogicalConstraint> {
+        let props = self.collect_prop_list(or_term, expand_nodes, visited)?;
+        Ok(LogicalConstraint::Not(props))
+    }
+
+    fn get_and_constraint(
+        &self,
+        or_term: SimpleTerm,
+        expand_nodes: bool,
+        visited: &mut Vec<String>,
+    ) -> Result<LogicalConstraint> {
+        let props = self.collect_prop_list(or_term, expand_nodes, visited)?;
+        Ok(LogicalConstraint::And(props))
+    }
+
+    fn get_or_constraint(
+        &self,
+        or_term: SimpleTerm,
+        expand_nodes: bool,
+        visited: &mut Vec<String>,
+    ) -> Result<LogicalConstraint> {
+        let props = self.collect_prop_list(or_term, expand_nodes, visited)?;
+        Ok(LogicalConstraint::Or(props))
+    }
+
+    fn get_xone_constraint(
+        &self,
+        or_term: SimpleTerm,
+        expand_nodes: bool,
+        visited: &mut Vec<String>,
+    ) -> Result<LogicalConstraint> {
+        let props = self.collect_prop_list(or_term, expand_nodes, visited)?;
+        Ok(LogicalConstraint::XOne(props))
+    }
+
+    fn collect_prop_list(
+        &self,
+        list_term: SimpleTerm,
+        expand_nodes: bool,
+        visited: &mut Vec<String>,
+    ) -> Result<Vec<BrickProperty>> {
+        self.collect_list(list_term)?
+            .into_iter()
+            .map(|term| self.collect_prop(term, expand_nodes, visited))
+            .collect()
+    }
+
+    /// Render a SHACL property path, complex paths use the SPARQL property path syntax.
+    fn path_value(&self, term: &SimpleTerm) -> Result<String> {
+        if term.iri().is_some() {
+            return local_name(term);
+        }
+
+        let sh = self.get_ns("sh")?;
+
+        for triple in self.graph.triples_matching([term], Any, Any) {
+            let triple = triple?;
+
+            if Term::eq(triple.p(), rdf::first) {
+                let paths = self
+                    .collect_list(term.clone())?
+                    .iter()
+                    .map(|el| self.path_value(el))
+                    .collect::<Result<Vec<String>>>()?;
+
+                return Ok(paths.join("/"));
+            } else if Term::eq(triple.p(), sh.get("inversePath")?) {
+                return Ok(format!("^{}", self.path_value(triple.o())?));
+            } else if Term::eq(triple.p(), sh.get("alternativePath")?) {
+                let paths = self
+                    .collect_list(triple.o().clone())?
+                    .iter()
+                    .map(|el| self.path_value(el))
+                    .collect::<Result<Vec<String>>>()?;
+
+                return Ok(format!("({})", paths.join("|")));
+            } else if Term::eq(triple.p(), sh.get("zeroOrMorePath")?) {
+                return Ok(format!("({})*", self.path_value(triple.o())?));
+            } else if Term::eq(triple.p(), sh.get("oneOrMorePath")?) {
+                return Ok(format!("({})+", self.path_value(triple.o())?));
+            } else if Term::eq(triple.p(), sh.get("zeroOrOnePath")?) {
+                return Ok(format!("({})?", self.path_value(triple.o())?));
+            }
+        }
+
+        Err(anyhow!("Invalid property path"))
+    }
+
+    /// Collect the members of an RDF list, in order.
+    fn collect_list(&self, mut list_term: SimpleTerm) -> Result<Vec<SimpleTerm<'_>>> {
+        let mut items = Vec::new();
+
+        while !Term::eq(&list_term, rdf::nil) {
+            let first = self
+                .graph
+                .triples_matching([&list_term], [&rdf::first], Any)
+                .next()
+                .ok_or_else(|| anyhow!("Expecting RDF list"))??;
+            items.push(first.o().clone());
+
+            let rest = self
+                .graph
+                .triples_matching([&list_term], [&rdf::rest], Any)
+                .next()
+                .ok_or_else(|| anyhow!("Expecting RDF list"))??;
+            list_term = rest.o().clone();
+        }
+
+        Ok(items)
+    }
+
+    /// String value of a term, IRIs are represented as CURIEs when possible.
+    fn term_value(&self, term: &SimpleTerm) -> Result<String> {
+        if let Some(iri) = term.iri() {
+            Ok(Curie::from_iri(iri.clone(), &self.prefixes)
+                .map_or_else(|_| iri.to_string(), |curie| curie.to_string()))
+        } else {
+            term.lexical_form()
+                .map(|val| val.to_string())
+                .ok_or_else(|| anyhow!("Expecting IRI or literal"))
+        }
+    }
+
+    /// The lexical form of the literal values of a subject property.
+    fn literal_of<S: Term, P: Term>(&self, subject: S, predicate: P) -> Result<String> {
+        self.graph
+            .triples_matching([subject], [predicate], Any)
+            .map(|triple| {
+                triple
+                    .map(|tr| {
+                        tr.o()
+                            .lexical_form()
+                            .map_or(String::new(), |v| v.to_string())
+                    })
+                    .map_err(Into::into)
+            })
+            .collect()
+    }
+
+    fn object_curies<S: Term, P: Term>(&self, subject: S, predicate: P) -> Result<Vec<Curie>> {
+        self.graph
+            .triples_matching([subject], [predicate], Any)
+            .map(|triple| {
+                triple
+                    .map_err(Into::into)
+                    .and_then(|tr| Curie::from_term(tr.o(), &self.prefixes))
+            })
+            .collect()
+    }
+
+    fn has_type<T: Term, C: Term>(&self, term: T, class: C) -> Result<bool> {
+        Ok(self
+            .graph
+            .triples_matching([term], [rdf::type_], [class])
+            .next()
+            .transpose()?
+            .is_some())
+    }
+
+    pub(crate) fn prefixes(&self) -> &PrefixNamespaceMap {
+        &self.prefixes
+    }
+
+    /// The IRI paths of the property shapes of a class and its superclasses.
+    pub(crate) fn property_paths(&self, curie: &Curie) -> Result<Vec<Curie>> {
+        let sh = self.get_ns("sh")?;
+
+        let classes = std::iter::once(curie.clone()).chain(
+            self.ancestors_of(curie, false)?
+                .into_iter()
+                .map(|relative| relative.class),
+        );
+
+        let mut paths = Vec::new();
+        for class in classes {
+            let shape = self.get_ns(&class.prefix)?.get(&class.local_name)?;
+
+            for triple in self
+                .graph
+                .triples_matching([shape], [sh.get("property")?], Any)
+            {
+                paths.extend(self.linked_curies(triple?.o(), sh.get("path")?, false)?);
+            }
+        }
+
+        Ok(paths)
+    }
+
+    fn get_ns(&self, prefix: &str) -> Result<&Namespace<String>> {
+        self.prefixes
+            .get_ns(prefix)
+            .ok_or_else(|| anyhow!("Missing prefix {}", prefix))
+    }
+}
+
+fn without_prefix(term: &SimpleTerm) -> Result<String> {
+    let val = term.iri().ok_or_else(|| anyhow!("Expecting IRI"))?;
+    let base = val.as_base();
+    let fragment = base.fragment().ok_or_else(|| anyhow!("Missing fragment"))?;
+
+    Ok(fragment.to_string())
+}
+
+/// Two property shapes constrain the same property if they share the path and,
+/// for qualified shapes, the qualified class.
+fn same_property(a: &BrickProperty, b: &BrickProperty) -> bool {
+    a.path == b.path
+        && a.qualified.as_ref().map(|q| &q.shape.class)
+            == b.qualified.as_ref().map(|q| &q.shape.class)
+}
+
+/// Tighten a property with the constraints an ancestor declares for the same path.
+/// Bounds are intersected, the other constraints are kept from the property unless
+/// it leaves them unset.
+fn merge_ancestor_property(cur: &mut BrickProperty, ancestor: &BrickProperty) {
+    fn highest<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(if b > a { b } else { a }),
+            (a, b) => a.or(b),
+        }
+    }
+
+    fn lowest<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(if b < a { b } else { a }),
+            (a, b) => a.or(b),
+        }
+    }
+
+    cur.min_count = highest(cur.min_count, ancestor.min_count);
+    cur.max_count = lowest(cur.max_count, ancestor.max_count);
+    cur.min_length = highest(cur.min_length, ancestor.min_length);
+    cur.max_length = lowest(cur.max_length, ancestor.max_length);
+    cur.min_inclusive = highest(cur.min_inclusive, ancestor.min_inclusive);
+    cur.max_inclusive = lowest(cur.max_inclusive, ancestor.max_inclusive);
+    cur.min_exclusive = highest(cur.min_exclusive, ancestor.min_exclusive);
+    cur.max_exclusive = lowest(cur.max_exclusive, ancestor.max_exclusive);
+
+    if cur.definition.is_empty() {
+        cur.definition.clone_from(&ancestor.definition);
+    }
+    if cur.class.local_name.is_empty() {
+        cur.class = ancestor.class.clone();
+        cur.subclass_of.clone_from(&ancestor.subclass_of);
+    }
+    if cur.pattern.is_none() {
+        cur.pattern.clone_from(&ancestor.pattern);
+    }
+    if cur.datatype.is_none() {
+        cur.datatype.clone_from(&ancestor.datatype);
+    }
+    if cur.node_kind.is_none() {
+        cur.node_kind.clone_from(&ancestor.node_kind);
+    }
+    if cur.qualified.is_none() {
+        cur.qualified.clone_from(&ancestor.qualified);
+    }
+    if cur.node.is_none() {
+        cur.node.clone_from(&ancestor.node);
+    }
+    if cur.has_value_of.is_empty() {
+        cur.has_value_of.clone_from(&ancestor.has_value_of);
+    }
+
+    if cur.one_of.is_empty() {
+        cur.one_of.clone_from(&ancestor.one_of);
+    } else if !ancestor.one_of.is_empty() {
+        cur.one_of.retain(|value| ancestor.one_of.contains(value));
+    }
+
+    cur.constraints.extend(ancestor.constraints.iter().cloned());
+    cur.logical_constraints
+        .extend(ancestor.logical_constraints.iter().cloned());
+}
+
+/// Local name of an IRI, either its fragment or its last path segment.
+fn local_name(term: &SimpleTerm) -> Result<String> {
+    let val = term.iri().ok_or_else(|| anyhow!("Expecting IRI"))?;
+
+    match val.as_base().fragment() {
+        Some(fragment) => Ok(fragment.to_string()),
+        None => Ok(val[val.rfind('/').map_or(0, |i| i + 1)..].to_string()),
+    }
+}
+
+/// Unique key of an IRI or blank node term.
+fn term_key(term: &SimpleTerm) -> String {
+    if let Some(id) = term.bnode_id() {
+        format!("_:{}", id.as_str())
+    } else {
+        term.iri().map_or(String::new(), |iri| iri.to_string())
+    }
+}
+
+fn parse_literal<T: std::str::FromStr>(term: &SimpleTerm) -> Result<T> {
+    let val = term
+        .lexical_form()
+        .ok_or_else(|| anyhow!("Expecting literal"))?;
+
+    val.parse()
+        .map_err(|_| anyhow!("Invalid literal value {}", val))
+}
+
+fn only_prefix(term: &SimpleTerm) -> String {
+    let val = term.iri().unwrap();
+
+    let idx = val.rfind('#').map(|v| v + 1).unwrap_or_default();
+    let begin = val[..idx].rfind('/').map(|v| v + 1).unwrap_or(idx);
+
+    val[begin..].to_string()
+}
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
+
+    use crate::{
+        brick::{Brick, LogicalConstraint, PropertyPairConstraint},
+        curie::Curie,
+    };
+
+    /// Parse the Brick ontology from `./Brick.ttl`.
+    pub(crate) fn parse_test_brick() -> Brick {
+        Brick::new(&std::fs::read_to_string("./Brick.ttl").unwrap()).unwrap()
+    }
+
+    /// The Brick ontology, parsed once and shared by the tests.
+    pub(crate) fn test_brick() -> &'static Brick {
+        static BRICK: std::sync::OnceLock<Brick> = std::sync::OnceLock::new();
+        BRICK.get_or_init(parse_test_brick)
+    }
+
+    #[test]
+    fn test_sub_class_of() {
+        let brick = test_brick();
+
+        assert!(brick
+            .sub_classes_of(&"brick:Point".try_into().unwrap())
+            .unwrap()
+            .contains(&Curie::try_from("brick:Sensor").unwrap()));
+    }
+
+    #[test]
+    fn test_super_class_of() {
+        let brick = test_brick();
+
+        assert!(brick
+            .super_classes_of(&"brick:Sensor".try_into().unwrap())
+            .unwrap()
+            .contains(&"brick:Point".try_into().unwrap()));
+
+        assert!(brick
+            .super_classes_of(&"brick:Capacity_Sensor".try_into().unwrap())
+            .unwrap()
+            .contains(&"brick:Sensor".try_into().unwrap()));
+
+        assert!(brick
+            .super_classes_of(&"brick:Point".try_into().unwrap())
+            .unwrap()
+            .contains(&"brick:Entity".try_into().unwrap()));
+    }
+
+    #[test]
+    fn test_class_tags() {
+        let brick = test_brick();
+
+        assert!(brick
+            .class_tags(&Curie::new("brick", "Setpoint"))
+            .unwrap()
+            .contains(&"Point".to_string()));
+    }
+
+    #[test]
+    fn test_class_desc() {
+        let brick = test_brick();
+
+        let desc = brick.class_desc(&Curie::new("brick", "Setpoint")).unwrap();
+
+        assert_eq!(desc.name, "Setpoint");
+        assert_eq!(desc.tags, ["Point", "Setpoint"]);
+        assert_eq!(
+            desc.definition,
+            "A Setpoint is an input value at which the desired property is set"
+        );
+        assert_eq!(
+            desc.types,
+            ["shacl#NodeShape".into(), "owl#Class".to_string()]
+        );
+        assert_eq!(desc.super_classes, vec!["brick:Point".try_into().unwrap()]);
+        assert_eq!(desc.tags, vec!["Point".to_string(), "Setpoint".to_string()]);
+    }
+
+    #[test]
+    fn test_deprecation() {
+        let brick = test_brick();
+
+        assert!(brick
+            .class_desc(&Curie::new("brick", "Setpoint"))
+            .unwrap()
+            .deprecation
+            .is_none());
+
+        let desc = brick
+            .class_desc(&Curie::new("brick", "Zone_Air_Temperature_Setpoint"))
+            .unwrap();
+        let deprecation = desc.deprecation.unwrap();
+        assert_eq!(deprecation.version, "1.3.0");
+        assert!(deprecation.message.contains("is deprecated"));
+        assert_eq!(
+            deprecation.replaced_by,
+            Some(Curie::new("brick", "Target_Zone_Air_Temperature_Setpoint"))
+        );
+
+        let deprecated = brick.deprecated_classes().unwrap();
+        assert!(deprecated.len() > 100);
+        assert!(deprecated
+            .iter()
+            .any(|d| d.class == Curie::new("brick", "Zone_Air_Temperature_Setpoint")));
+        assert!(deprecated.windows(2).all(|w| w[0].class < w[1].class));
+    }
+
+    #[test]
+    fn test_deprecation_malformed() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+
+            brick:Old a owl:Class ; owl:deprecated "maybe" .
+            brick:Older a owl:Class ; owl:deprecated true .
+            brick:Oldest a owl:Class ; owl:deprecated "1"^^<http://www.w3.org/2001/XMLSchema#boolean> .
+            brick:Current a owl:Class ; owl:deprecated "0"^^<http://www.w3.org/2001/XMLSchema#boolean> .
+            "#,
+        )
+        .unwrap();
+
+        let deprecated = brick.deprecated_classes().unwrap();
+        assert_eq!(
+            deprecated.iter().map(|d| &d.class).collect::<Vec<_>>(),
+            [
+                &Curie::new("brick", "Old"),
+                &Curie::new("brick", "Older"),
+                &Curie::new("brick", "Oldest")
+            ]
+        );
+        assert_eq!(
+            deprecated
+                .iter()
+                .map(|d| d.deprecation.malformed_flag.as_deref())
+                .collect::<Vec<_>>(),
+            [Some("maybe"), None, None]
+        );
+    }
+
+    #[test]
+    fn test_deprecation_node() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+
+            brick:Old a owl:Class ; owl:deprecated true ;
+                brick:deprecation [
+                    brick:deprecatedInVersion "1.3.1" ;
+                    brick:deprecationMitigationMessage "Old is deprecated in favor of New" ;
+                    brick:isReplacedBy brick:New
+                ] .
+            brick:New a owl:Class .
+            "#,
+        )
+        .unwrap();
+
+        let deprecated = brick.deprecated_classes().unwrap();
+        assert_eq!(deprecated.len(), 1);
+        let deprecation = &deprecated[0].deprecation;
+        assert_eq!(deprecation.version, "1.3.1");
+        assert_eq!(deprecation.message, "Old is deprecated in favor of New");
+        assert_eq!(deprecation.replaced_by, Some(Curie::new("brick", "New")));
+    }
+
+    #[test]
+    fn test_class_props() {
+        let brick = test_brick();
+
+        let props = brick
+            .class_properties(&"brick:Location".try_into().unwrap())
+            .unwrap();
+        assert!(!props.is_empty());
+
+        assert!(props
+            .iter()
+            .any(|p| p.path == "hasPoint" && p.class == Curie::new("brick", "Point")));
+
+        assert!(props.iter().any(|p| p.path == "isPartOf"
+            && p.logical_constraints.iter().any(
+                |prop| matches!(prop, LogicalConstraint::Or(el) if el[0].class == Curie::new("brick","Location"))
+            )));
+
+        let props = brick
+            .class_properties(&Curie::try_from("brick:Site").unwrap())
+            .unwrap();
+        assert!(!props.is_empty());
 
-    use crate::{
-        brick::{Brick, LogicalConstraint},
-        curie::Curie,
-    };
-    use std::io::prelude::*;
+        assert!(props.iter().any(|p| p.path == "hasPart"
+            && !p.logical_constraints.is_empty()
+            && matches!(&p.logical_constraints[0], LogicalConstraint::Or(el) if el[0].class == Curie::new("brick","Building"))));
+    }
+
+    #[test]
+    fn test_class_props_logical_members() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+
+            brick:Thing a owl:Class ;
+                sh:property [ sh:path brick:hasPart ;
+                    sh:or ( [ sh:class brick:Part ; sh:message "A part." ]
+                            [ sh:class brick:Piece ; sh:message "A piece." ] ) ] .
+            "#,
+        )
+        .unwrap();
+
+        let props = brick
+            .class_properties(&Curie::new("brick", "Thing"))
+            .unwrap();
+        assert_eq!(props.len(), 1);
+
+        let LogicalConstraint::Or(members) = &props[0].logical_constraints[0] else {
+            panic!("Expecting sh:or");
+        };
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].class, Curie::new("brick", "Part"));
+        assert_eq!(members[0].definition, "A part.");
+        assert_eq!(members[1].class, Curie::new("brick", "Piece"));
+        assert_eq!(members[1].definition, "A piece.");
+    }
+
+    #[test]
+    fn test_class_props_constraints() {
+        let brick = test_brick();
+
+        let props = brick
+            .class_properties(&"bsh:AzimuthShape".try_into().unwrap())
+            .unwrap();
+
+        let unit = props.iter().find(|p| p.path == "hasUnit").unwrap();
+        assert_eq!(unit.min_count, Some(1));
+        assert_eq!(unit.max_count, Some(1));
+        assert_eq!(unit.one_of.len(), 13);
+        assert_eq!(unit.one_of[0], "unit:MIL");
+        assert!(unit.one_of.contains(&"unit:DEG".to_string()));
+
+        let value = props.iter().find(|p| p.path == "value").unwrap();
+        assert_eq!(value.min_inclusive, Some(0.0));
+        assert_eq!(value.max_inclusive, Some(360.0));
+        assert_eq!(value.min_exclusive, None);
 
-    fn ensure_brick() -> Brick {
-        let mut file = std::fs::File::open("./Brick.ttl").unwrap();
+        let props = brick
+            .class_properties(&"bsh:ResolutionShape".try_into().unwrap())
+            .unwrap();
+        assert_eq!(props[0].min_exclusive, Some(0.0));
+
+        let props = brick
+            .class_properties(&"bsh:PhaseCountShape".try_into().unwrap())
+            .unwrap();
+        assert_eq!(props[0].one_of, ["1", "2", "3", "Total"]);
 
-        let mut buffer = String::new();
-        file.read_to_string(&mut buffer).unwrap();
+        let props = brick
+            .class_properties(&"s223:BACnetExternalReference".try_into().unwrap())
+            .unwrap();
+        assert!(props.iter().any(|p| p.path == "device-identifier"
+            && p.min_count == Some(0)
+            && p.pattern.as_deref() == Some("^[A-Za-z0-9-]+,[1-9][0-9]*$")));
 
-        Brick::new(&buffer).unwrap()
+        let props = brick
+            .class_properties(&"g36:ChilledWaterValve".try_into().unwrap())
+            .unwrap();
+        assert!(props
+            .iter()
+            .any(|p| p.path == "hasMedium" && p.has_value_of == "s223:Water-ChilledWater"));
     }
 
     #[test]
-    fn test_sub_class_of() {
-        let brick = ensure_brick();
+    fn test_class_props_pair_constraints() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+
+            brick:Schedule_Slot a sh:NodeShape ;
+                sh:property [ sh:path brick:start ;
+                    sh:lessThan brick:end ;
+                    sh:lessThanOrEquals brick:deadline ] ,
+                [ sh:path brick:owner ;
+                    sh:equals brick:creator ;
+                    sh:disjoint brick:reviewer ] .
+            "#,
+        )
+        .unwrap();
 
-        assert!(brick
-            .sub_classes_of(&"brick:Point".try_into().unwrap())
-            .unwrap()
-            .contains(&Curie::try_from("brick:Sensor").unwrap()));
+        let props = brick
+            .class_properties(&"brick:Schedule_Slot".try_into().unwrap())
+            .unwrap();
+
+        let start = props.iter().find(|p| p.path == "start").unwrap();
+        assert!(start.constraints.iter().any(
+            |c| matches!(c, PropertyPairConstraint::LessThan(curie) if *curie == Curie::new("brick", "end"))
+        ));
+        assert!(start.constraints.iter().any(
+            |c| matches!(c, PropertyPairConstraint::LessThanOrEqual(curie) if *curie == Curie::new("brick", "deadline"))
+        ));
+
+        let owner = props.iter().find(|p| p.path == "owner").unwrap();
+        assert!(owner.constraints.iter().any(
+            |c| matches!(c, PropertyPairConstraint::Equal(curie) if *curie == Curie::new("brick", "creator"))
+        ));
+        assert!(owner.constraints.iter().any(
+            |c| matches!(c, PropertyPairConstraint::Disjoint(curie) if *curie == Curie::new("brick", "reviewer"))
+        ));
     }
 
     #[test]
-    fn test_super_class_of() {
-        let brick = ensure_brick();
+    fn test_class_props_qualified() {
+        let brick = test_brick();
 
-        assert!(brick
-            .super_classes_of(&"brick:Sensor".try_into().unwrap())
-            .unwrap()
-            .contains(&"brick:Point".try_into().unwrap()));
+        let props = brick
+            .class_properties(&"bsh:hasHotColdDeck".try_into().unwrap())
+            .unwrap();
+        assert_eq!(props.len(), 2);
 
-        assert!(brick
-            .super_classes_of(&"brick:Capacity_Sensor".try_into().unwrap())
-            .unwrap()
-            .contains(&"brick:Sensor".try_into().unwrap()));
+        let hot_deck = props
+            .iter()
+            .filter_map(|p| p.qualified.as_ref())
+            .find(|q| q.shape.class == Curie::new("brick", "Hot_Deck"))
+            .unwrap();
+        assert_eq!(hot_deck.min_count, Some(1));
+        assert_eq!(hot_deck.max_count, Some(1));
+        assert!(hot_deck.disjoint);
 
-        assert!(brick
-            .super_classes_of(&"brick:Point".try_into().unwrap())
-            .unwrap()
-            .contains(&"brick:Entity".try_into().unwrap()));
+        let props = brick
+            .class_properties(&"g36:ChilledWaterCoil".try_into().unwrap())
+            .unwrap();
+        let valve = props[0].qualified.as_ref().unwrap();
+        assert_eq!(props[0].path, "connectedTo");
+        assert_eq!(valve.shape.class, Curie::new("g36", "ChilledWaterValve"));
+        assert_eq!(valve.min_count, Some(1));
+        assert_eq!(valve.max_count, None);
+        assert!(!valve.disjoint);
     }
 
     #[test]
-    fn test_class_tags() {
-        let brick = ensure_brick();
+    fn test_class_props_node() {
+        let brick = test_brick();
 
-        assert!(brick
-            .class_tags(&Curie::new("brick", "Setpoint"))
-            .unwrap()
-            .contains(&"Point".to_string()));
+        let props = brick
+            .class_properties(&"brick:Chiller".try_into().unwrap())
+            .unwrap();
+        let capacity = props.iter().find(|p| p.path == "coolingCapacity").unwrap();
+        let node = capacity.node.as_ref().unwrap();
+        assert_eq!(node.shape, Some(Curie::new("bsh", "CoolingCapacityShape")));
+        assert!(node.properties.is_empty());
+
+        let props = brick
+            .class_properties_expanded(&"brick:Chiller".try_into().unwrap())
+            .unwrap();
+        let capacity = props.iter().find(|p| p.path == "coolingCapacity").unwrap();
+        let node = capacity.node.as_ref().unwrap();
+        assert!(node
+            .properties
+            .iter()
+            .any(|p| p.path == "hasUnit" && p.one_of.contains(&"unit:TON_FG".to_string())));
     }
 
     #[test]
-    fn test_class_desc() {
-        let brick = ensure_brick();
+    fn test_class_props_node_cycle() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
 
-        let desc = brick.class_desc(&Curie::new("brick", "Setpoint")).unwrap();
+            brick:Parent a sh:NodeShape ;
+                sh:property [ sh:path brick:hasPart ; sh:node brick:Child ] .
 
-        assert_eq!(desc.name, "Setpoint");
-        assert_eq!(desc.tags, ["Point", "Setpoint"]);
+            brick:Child a sh:NodeShape ;
+                sh:property [ sh:path brick:isPartOf ; sh:node brick:Parent ] .
+            "#,
+        )
+        .unwrap();
+
+        let props = brick
+            .class_properties_expanded(&"brick:Parent".try_into().unwrap())
+            .unwrap();
+
+        let child = props[0].node.as_ref().unwrap();
+        assert_eq!(child.shape, Some(Curie::new("brick", "Child")));
+
+        let parent = child.properties[0].node.as_ref().unwrap();
+        assert_eq!(parent.shape, Some(Curie::new("brick", "Parent")));
+        assert!(parent.properties.is_empty());
+    }
+
+    #[test]
+    fn test_class_props_qualified_cycle() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+
+            brick:Holder a sh:NodeShape ;
+                sh:property [ sh:path brick:hasPart ; sh:qualifiedValueShape brick:PartShape ] .
+
+            brick:PartShape sh:class brick:Part ;
+                sh:qualifiedValueShape brick:PartShape .
+            "#,
+        )
+        .unwrap();
+
+        let props = brick
+            .class_properties(&"brick:Holder".try_into().unwrap())
+            .unwrap();
+
+        let qualified = props[0].qualified.as_ref().unwrap();
         assert_eq!(
-            desc.definition,
-            "A Setpoint is an input value at which the desired property is set"
+            qualified.value_shape,
+            Some(Curie::new("brick", "PartShape"))
         );
+        assert_eq!(qualified.shape.class, Curie::new("brick", "Part"));
+
+        let cut = qualified.shape.qualified.as_ref().unwrap();
+        assert_eq!(cut.value_shape, Some(Curie::new("brick", "PartShape")));
+        assert!(cut.shape.class.local_name.is_empty());
+    }
+
+    #[test]
+    fn test_shape() {
+        let brick = test_brick();
+
+        let shape = brick
+            .shape(&"bsh:hasHotColdDeck".try_into().unwrap())
+            .unwrap();
+        assert_eq!(shape.target_classes, [Curie::new("brick", "DDAHU")]);
+        assert_eq!(shape.properties.len(), 2);
+        assert!(!shape.closed);
+
+        let shape = brick
+            .shape(&"s223:ControllerRoleShape".try_into().unwrap())
+            .unwrap();
+        assert_eq!(shape.target_subjects_of, [Curie::new("s223", "executes")]);
+        assert!(shape.target_classes.is_empty());
+        assert_eq!(shape.properties[0].has_value_of, "s223:Role-Controller");
+
+        let shape = brick
+            .shape(&"s223:DomainSpace".try_into().unwrap())
+            .unwrap();
+        assert!(shape.properties.iter().any(|p| p.path == "^encloses"));
+
+        let shapes = brick
+            .shapes_targeting(&"brick:DDAHU".try_into().unwrap())
+            .unwrap();
+        assert!(shapes
+            .iter()
+            .any(|s| s.shape == Curie::new("brick", "DDAHU")));
+        assert!(shapes
+            .iter()
+            .any(|s| s.shape == Curie::new("bsh", "hasHotColdDeck")));
+
+        assert!(brick.shape(&"brick:hasPoint".try_into().unwrap()).is_err());
+
+        let shapes = brick
+            .shapes_targeting(&"bsh:hasHotColdDeck".try_into().unwrap())
+            .unwrap();
+        assert!(shapes.is_empty());
+    }
+
+    #[test]
+    fn test_shape_closed() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+
+            brick:Closed_Shape a sh:NodeShape ;
+                sh:targetObjectsOf brick:feeds ;
+                sh:severity sh:Warning ;
+                sh:message "Only feeds allowed" ;
+                sh:closed true ;
+                sh:ignoredProperties ( rdf:type brick:hasTag ) ;
+                sh:property [ sh:path ( brick:feeds [ sh:inversePath brick:hasPart ] ) ] ,
+                    [ sh:path [ sh:alternativePath ( brick:feeds [ sh:zeroOrMorePath brick:isFedBy ] ) ] ] .
+            "#,
+        )
+        .unwrap();
+
+        let shape = brick
+            .shape(&"brick:Closed_Shape".try_into().unwrap())
+            .unwrap();
+        assert_eq!(shape.target_objects_of, [Curie::new("brick", "feeds")]);
+        assert_eq!(shape.severity, Some(Curie::new("sh", "Warning")));
+        assert_eq!(shape.message, "Only feeds allowed");
+        assert!(shape.closed);
         assert_eq!(
-            desc.types,
-            ["shacl#NodeShape".into(), "owl#Class".to_string()]
+            shape.ignored_properties,
+            [Curie::new("rdf", "type"), Curie::new("brick", "hasTag")]
         );
-        assert_eq!(desc.super_classes, vec!["brick:Point".try_into().unwrap()]);
-        assert_eq!(desc.tags, vec!["Point".to_string(), "Setpoint".to_string()]);
+        assert!(shape.properties.iter().any(|p| p.path == "feeds/^hasPart"));
+        assert!(shape
+            .properties
+            .iter()
+            .any(|p| p.path == "(feeds|(isFedBy)*)"));
+
+        let shapes = brick
+            .shapes_targeting(&"brick:feeds".try_into().unwrap())
+            .unwrap();
+        assert_eq!(shapes.len(), 1);
     }
 
     #[test]
-    fn test_class_props() {
-        let brick = ensure_brick();
+    fn test_effective_properties() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+
+            brick:Equipment a sh:NodeShape ;
+                sh:property [ sh:path brick:hasPoint ; sh:minCount 0 ; sh:maxCount 5 ;
+                        sh:class brick:Point ] ,
+                    [ sh:path brick:isPartOf ; sh:class brick:Equipment ] .
+
+            brick:HVAC_Equipment a sh:NodeShape ;
+                rdfs:subClassOf brick:Equipment ;
+                sh:property [ sh:path brick:hasPoint ; sh:minCount 1 ; sh:maxCount 10 ] ,
+                    [ sh:path brick:hasLocation ; sh:class brick:Room ] ,
+                    [ sh:path brick:hasLocation ; sh:class brick:Floor ] .
+
+            brick:AHU a sh:NodeShape ;
+                rdfs:subClassOf brick:HVAC_Equipment ;
+                sh:property [ sh:path brick:feeds ; sh:class brick:VAV ] .
+            "#,
+        )
+        .unwrap();
 
         let props = brick
-            .class_properties(&"brick:Location".try_into().unwrap())
+            .effective_properties(&"brick:AHU".try_into().unwrap())
             .unwrap();
-        assert!(!props.is_empty());
+        assert_eq!(props.len(), 5);
 
-        assert!(props
+        assert_eq!(props[0].property.path, "feeds");
+        assert_eq!(props[0].defined_in, Curie::new("brick", "AHU"));
+
+        let point = props
             .iter()
-            .any(|p| p.path == "hasPoint" && p.class == Curie::new("brick", "Point")));
+            .find(|p| p.property.path == "hasPoint")
+            .unwrap();
+        assert_eq!(point.defined_in, Curie::new("brick", "HVAC_Equipment"));
+        assert_eq!(point.overrides, [Curie::new("brick", "Equipment")]);
+        assert_eq!(point.property.min_count, Some(1));
+        assert_eq!(point.property.max_count, Some(5));
+        assert_eq!(point.property.class, Curie::new("brick", "Point"));
 
-        assert!(props.iter().any(|p| p.path == "isPartOf"
-            && p.logical_constraints.iter().any(
-                |prop| matches!(prop, LogicalConstraint::Or(el) if el[0].class == Curie::new("brick","Location"))
-            )));
+        let locations = props
+            .iter()
+            .filter(|p| p.property.path == "hasLocation")
+            .collect::<Vec<_>>();
+        assert_eq!(locations.len(), 2);
+        assert!(locations.iter().all(|p| p.overrides.is_empty()));
+        assert_eq!(locations[0].property.class, Curie::new("brick", "Room"));
+        assert_eq!(locations[1].property.class, Curie::new("brick", "Floor"));
+
+        let part = props
+            .iter()
+            .find(|p| p.property.path == "isPartOf")
+            .unwrap();
+        assert_eq!(part.defined_in, Curie::new("brick", "Equipment"));
+        assert!(part.overrides.is_empty());
+
+        let brick = test_brick();
 
         let props = brick
-            .class_properties(&Curie::try_from("brick:Site").unwrap())
+            .effective_properties(&"brick:Building".try_into().unwrap())
             .unwrap();
-        assert!(!props.is_empty());
+        assert!(props.iter().any(
+            |p| p.property.path == "isFedBy" && p.defined_in == Curie::new("brick", "Location")
+        ));
+        assert!(props.iter().any(|p| p.property.path == "hasPart"
+            && p.defined_in == Curie::new("brick", "Building")
+            && p.overrides.contains(&Curie::new("brick", "Location"))));
+    }
 
-        assert!(props.iter().any(|p| p.path == "hasPart"
-            && !p.logical_constraints.is_empty()
-            && matches!(&p.logical_constraints[0], LogicalConstraint::Or(el) if el[0].class == Curie::new("brick","Building"))));
+    #[test]
+    fn test_ancestors_descendants() {
+        let brick = test_brick();
+
+        let ancestors = brick
+            .ancestors_of(
+                &"brick:Supply_Air_Temperature_Sensor".try_into().unwrap(),
+                false,
+            )
+            .unwrap();
+        let sensor = ancestors
+            .iter()
+            .find(|r| r.class == Curie::new("brick", "Temperature_Sensor"))
+            .unwrap();
+        assert_eq!(sensor.distance, 2);
+        assert_eq!(sensor.paths.len(), 1);
+        assert!(ancestors
+            .iter()
+            .any(|r| r.class == Curie::new("brick", "Point")));
+        assert!(ancestors.windows(2).all(|w| w[0].distance <= w[1].distance));
+
+        let descendants = brick
+            .descendants_of(&"brick:Point".try_into().unwrap(), false)
+            .unwrap();
+        assert!(descendants.len() > 900);
+        assert!(descendants
+            .iter()
+            .any(|r| r.class == Curie::new("brick", "Supply_Air_Temperature_Sensor")));
+
+        let descendants = brick
+            .descendants_of(&"brick:Entity".try_into().unwrap(), true)
+            .unwrap();
+        assert!(descendants
+            .iter()
+            .all(|r| r.paths[0].len() as u32 == r.distance + 1));
+    }
+
+    #[test]
+    fn test_ancestors_all_paths() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+            brick:Sensor rdfs:subClassOf brick:Point .
+            brick:Temperature_Sensor rdfs:subClassOf brick:Sensor .
+            brick:Air_Temperature_Sensor rdfs:subClassOf brick:Temperature_Sensor, brick:Point .
+            brick:Point rdfs:subClassOf brick:Air_Temperature_Sensor .
+            "#,
+        )
+        .unwrap();
+
+        let ancestors = brick
+            .ancestors_of(&"brick:Air_Temperature_Sensor".try_into().unwrap(), true)
+            .unwrap();
+        assert_eq!(ancestors.len(), 3);
+
+        let point = &ancestors[0];
+        assert_eq!(point.class, Curie::new("brick", "Point"));
+        assert_eq!(point.distance, 1);
+        assert_eq!(point.paths.len(), 2);
+        assert_eq!(point.paths[0].len(), 2);
+        assert_eq!(
+            point.paths[1],
+            [
+                Curie::new("brick", "Air_Temperature_Sensor"),
+                Curie::new("brick", "Temperature_Sensor"),
+                Curie::new("brick", "Sensor"),
+                Curie::new("brick", "Point"),
+            ]
+        );
+
+        let descendants = brick
+            .descendants_of(&"brick:Point".try_into().unwrap(), false)
+            .unwrap();
+        assert_eq!(descendants.len(), 3);
+    }
+
+    #[test]
+    fn test_descendants_all_paths() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+            brick:B rdfs:subClassOf brick:A .
+            brick:C rdfs:subClassOf brick:A .
+            brick:D rdfs:subClassOf brick:B, brick:C .
+            brick:E rdfs:subClassOf brick:D .
+            brick:F rdfs:subClassOf brick:D .
+            brick:G rdfs:subClassOf brick:E, brick:F .
+            "#,
+        )
+        .unwrap();
+
+        let class = |name| Curie::new("brick", name);
+
+        let descendants = brick.descendants_of(&class("A"), true).unwrap();
+        assert_eq!(descendants.len(), 6);
+
+        let e = descendants.iter().find(|r| r.class == class("E")).unwrap();
+        assert_eq!(e.distance, 3);
+        assert_eq!(e.paths.len(), 2);
+        assert!(e
+            .paths
+            .contains(&vec![class("A"), class("C"), class("D"), class("E")]));
+
+        let g = descendants.iter().find(|r| r.class == class("G")).unwrap();
+        assert_eq!(g.distance, 4);
+        assert_eq!(g.paths.len(), 4);
+        assert!(g.paths.iter().all(|path| path.len() == 5));
+
+        let descendants = brick.descendants_of(&class("A"), false).unwrap();
+        assert!(descendants.iter().all(|r| r.paths.len() == 1));
+    }
+
+    #[test]
+    fn test_class_hierarchy_helpers() {
+        let brick = test_brick();
+
+        let sat: Curie = "brick:Supply_Air_Temperature_Sensor".try_into().unwrap();
+        let rat: Curie = "brick:Return_Air_Temperature_Sensor".try_into().unwrap();
+        let sensor: Curie = "brick:Sensor".try_into().unwrap();
+        let setpoint: Curie = "brick:Setpoint".try_into().unwrap();
+        let point: Curie = "brick:Point".try_into().unwrap();
+
+        assert!(brick.is_subclass_of(&sat, &sensor));
+        assert!(brick.is_subclass_of(&sat, &sat));
+        assert!(!brick.is_subclass_of(&sensor, &sat));
+        assert!(!brick.is_subclass_of(&sat, &setpoint));
+
+        let unknown = Curie::new("brick", "NotAClass");
+        assert!(!brick.is_subclass_of(&unknown, &unknown));
+        assert_eq!(brick.class_distance(&unknown, &unknown), None);
+        assert!(brick.is_subclass_of(
+            &Curie::new("brick", "Entity"),
+            &Curie::new("brick", "Entity")
+        ));
+
+        let common = brick.common_ancestors(&sat, &setpoint);
+        assert_eq!(common[0], point);
+        assert!(!common.contains(&sensor));
+
+        assert_eq!(
+            brick.lowest_common_ancestor(&[sat.clone(), rat.clone()]),
+            Some(Curie::new("brick", "Air_Temperature_Sensor"))
+        );
+        assert_eq!(
+            brick.lowest_common_ancestor(&[sat.clone(), rat.clone(), setpoint.clone()]),
+            Some(point.clone())
+        );
+        assert_eq!(brick.lowest_common_ancestor(&[]), None);
+
+        assert_eq!(brick.class_distance(&sat, &sat), Some(0));
+        assert_eq!(brick.class_distance(&sat, &rat), Some(2));
+        assert_eq!(brick.class_distance(&setpoint, &point), Some(1));
+        assert_eq!(
+            brick.class_distance(&sat, &Curie::new("brick", "Unknown")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_enumerate_ontology() {
+        let brick = test_brick();
+
+        let classes = brick.classes().unwrap();
+        assert!(classes.len() > 1000);
+        assert!(classes.contains(&Curie::new("brick", "AHU")));
+        assert!(!classes.contains(&Curie::new("brick", "hasPoint")));
+        assert!(classes.windows(2).all(|w| w[0] < w[1]));
+
+        let relationships = brick.object_properties().unwrap();
+        assert!(relationships.contains(&Curie::new("brick", "hasPoint")));
+        assert!(relationships.contains(&Curie::new("brick", "feeds")));
+
+        let properties = brick.datatype_properties().unwrap();
+        assert!(properties.contains(&Curie::new("brick", "latitude")));
+        assert!(!properties.contains(&Curie::new("brick", "hasPoint")));
+
+        let roots = brick.root_classes().unwrap();
+        assert!(roots.contains(&Curie::new("brick", "Entity")));
+        assert!(!roots.contains(&Curie::new("brick", "Point")));
+
+        let categories = brick.top_level_categories().unwrap();
+        for category in ["Collection", "Equipment", "Location", "Point"] {
+            assert!(categories.contains(&Curie::new("brick", category)));
+        }
+    }
+
+    #[test]
+    fn test_enumerate_unknown_namespace() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+
+            brick:Known a owl:Class .
+            <http://example.com/vocab#Unknown> a owl:Class .
+            [] a owl:Class .
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(brick.classes().unwrap(), [Curie::new("brick", "Known")]);
+    }
+
+    #[test]
+    fn test_class_tree() {
+        let brick = test_brick();
+
+        let tree = brick
+            .class_tree(&"brick:Point".try_into().unwrap(), Some(1), false)
+            .unwrap();
+        assert_eq!(tree.label, "Point");
+        assert!(tree.children.iter().all(|c| c.children.is_empty()));
+
+        let sensor = tree
+            .children
+            .iter()
+            .find(|c| c.curie == Curie::new("brick", "Sensor"))
+            .unwrap();
+        assert_eq!(sensor.label, "Sensor");
+        assert_eq!(sensor.tag_count, 2);
+        assert!(!sensor.deprecated);
+
+        let tree = brick
+            .class_tree(&"brick:Location".try_into().unwrap(), Some(1), false)
+            .unwrap();
+        assert!(tree.deprecated);
+
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+            brick:Sensor rdfs:subClassOf brick:Point .
+            brick:Setpoint rdfs:subClassOf brick:Point .
+            brick:Temperature_Sensor rdfs:subClassOf brick:Sensor, brick:Setpoint .
+            brick:Point rdfs:subClassOf brick:Temperature_Sensor .
+            "#,
+        )
+        .unwrap();
+
+        let tree = brick
+            .class_tree(&"brick:Point".try_into().unwrap(), None, false)
+            .unwrap();
+        assert_eq!(tree.children.len(), 2);
+        assert_eq!(tree.children[0].children.len(), 1);
+        assert_eq!(tree.children[1].children.len(), 0);
+
+        let tree = brick
+            .class_tree(&"brick:Point".try_into().unwrap(), None, true)
+            .unwrap();
+        assert_eq!(tree.children[0].children.len(), 1);
+        assert_eq!(tree.children[1].children.len(), 1);
+        assert!(tree.children[1].children[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_entity_property_desc() {
+        let brick = test_brick();
+
+        let area = brick
+            .entity_property_desc(&Curie::new("brick", "area"))
+            .unwrap();
+        assert_eq!(area.label, "Area");
+        assert_eq!(area.value_shape, Some(Curie::new("bsh", "AreaShape")));
+        assert_eq!(
+            area.units,
+            [Curie::new("unit", "FT2"), Curie::new("unit", "M2")]
+        );
+        assert!(area.datatypes.contains(&Curie::new("xsd", "double")));
+
+        let gross_area = brick
+            .entity_property_desc(&Curie::new("brick", "grossArea"))
+            .unwrap();
+        assert_eq!(gross_area.units, area.units);
+
+        let year_built = brick
+            .entity_property_desc(&Curie::new("brick", "yearBuilt"))
+            .unwrap();
+        assert_eq!(
+            year_built.datatypes,
+            [Curie::new("xsd", "nonNegativeInteger")]
+        );
+        assert!(year_built.units.is_empty());
+
+        let phases = brick
+            .entity_property_desc(&Curie::new("brick", "electricalPhases"))
+            .unwrap();
+        assert_eq!(phases.enumeration, ["A", "B", "C", "AB", "BC", "AC", "ABC"]);
+
+        let azimuth = brick
+            .entity_property_desc(&Curie::new("brick", "azimuth"))
+            .unwrap();
+        assert_eq!(azimuth.min_inclusive, Some(0.0));
+        assert_eq!(azimuth.max_inclusive, Some(360.0));
+
+        let resolution = brick
+            .entity_property_desc(&Curie::new("brick", "resolution"))
+            .unwrap();
+        assert_eq!(resolution.min_exclusive, Some(0.0));
+        assert_eq!(resolution.max_exclusive, None);
+
+        assert!(brick
+            .entity_property_desc(&Curie::new("brick", "notAProperty"))
+            .is_err());
+    }
+
+    /// An ontology declaring `brick:height`, whose values are in meters or feet,
+    /// followed by the given Turtle.
+    fn height_brick(turtle: &str) -> Brick {
+        Brick::new(&format!(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix bsh: <https://brickschema.org/schema/BrickShape#> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+            @prefix skos: <http://www.w3.org/2004/02/skos/core#> .
+            @prefix unit: <http://qudt.org/vocab/unit/> .
+
+            brick:height rdfs:label "Height" ; skos:definition "Height of the entity" .
+            bsh:hasheightShape sh:node bsh:HeightShape ; sh:path brick:height .
+            bsh:HeightShape sh:property [ sh:in ( unit:M unit:FT ) ; sh:path brick:hasUnit ] .
+            {turtle}
+            "#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_entity_property_quantity_kind() {
+        let volume = test_brick()
+            .entity_property_desc(&Curie::new("brick", "volume"))
+            .unwrap();
+        assert_eq!(volume.value_shape, Some(Curie::new("bsh", "VolumeShape")));
+        assert_eq!(
+            volume.units,
+            [Curie::new("unit", "FT3"), Curie::new("unit", "M3")]
+        );
+        assert_eq!(volume.quantity_kind, Some(Curie::new("brick", "Volume")));
+
+        let brick = height_brick(
+            r#"
+            @prefix qudt: <http://qudt.org/schema/qudt/> .
+            @prefix quantitykind: <http://qudt.org/vocab/quantitykind/> .
+
+            brick:speed rdfs:label "Speed" .
+            bsh:hasspeedShape sh:node bsh:SpeedShape ; sh:path brick:speed .
+            bsh:SpeedShape sh:node bsh:SpeedKindShape .
+            bsh:SpeedKindShape sh:property [ sh:hasValue quantitykind:Speed ; sh:path qudt:hasQuantityKind ] .
+
+            bsh:HeightShape rdfs:subClassOf bsh:LengthShape .
+            bsh:LengthShape sh:property [ sh:hasValue quantitykind:Length ; sh:path qudt:hasQuantityKind ] .
+            "#,
+        );
+
+        let speed = brick
+            .entity_property_desc(&Curie::new("brick", "speed"))
+            .unwrap();
+        assert_eq!(
+            speed.quantity_kind,
+            Some(Curie::new("quantitykind", "Speed"))
+        );
+
+        let height = brick
+            .entity_property_desc(&Curie::new("brick", "height"))
+            .unwrap();
+        assert_eq!(height.definition, "Height of the entity");
+        assert_eq!(height.units.len(), 2);
+        assert_eq!(height.quantity_kind, None);
+    }
+
+    #[test]
+    fn test_substances_and_quantities() {
+        let brick = test_brick();
+
+        let desc = brick
+            .class_desc(&Curie::new("brick", "Supply_Air_Temperature_Sensor"))
+            .unwrap();
+        assert_eq!(
+            desc.substances,
+            [
+                Curie::new("brick", "Discharge_Air"),
+                Curie::new("brick", "Supply_Air")
+            ]
+        );
+        assert_eq!(desc.quantities, [Curie::new("quantitykind", "Temperature")]);
+
+        let supply_air = Curie::new("brick", "Supply_Air");
+        let temperature = Curie::new("quantitykind", "Temperature");
+
+        let points = brick
+            .points_for(Some(&supply_air), Some(&temperature))
+            .unwrap();
+        assert!(points.contains(&Curie::new("brick", "Supply_Air_Temperature_Sensor")));
+        assert!(!points.contains(&Curie::new("brick", "Return_Air_Temperature_Sensor")));
+        assert!(!points.contains(&Curie::new("brick", "Supply_Air_Flow_Sensor")));
+
+        let air_points = brick
+            .points_for(Some(&Curie::new("brick", "Air")), Some(&temperature))
+            .unwrap();
+        assert!(air_points.len() > points.len());
+        assert!(air_points.contains(&Curie::new("brick", "Return_Air_Temperature_Sensor")));
+
+        let supply_air_points = brick.points_for(Some(&supply_air), None).unwrap();
+        assert!(supply_air_points.contains(&Curie::new("brick", "Supply_Air_Flow_Sensor")));
+    }
+
+    #[test]
+    fn test_aliases() {
+        let brick = test_brick();
+
+        let ahu = Curie::new("brick", "Air_Handling_Unit");
+        assert_eq!(
+            brick.canonical_class(&Curie::new("brick", "AHU")).unwrap(),
+            ahu
+        );
+        assert_eq!(brick.canonical_class(&ahu).unwrap(), ahu);
+
+        let aliases = brick.aliases_of(&ahu).unwrap();
+        assert!(aliases.contains(&Curie::new("brick", "AHU")));
+        assert!(aliases.contains(&Curie::new("brick", "Air_Handler_Unit")));
+        assert_eq!(
+            brick.aliases_of(&Curie::new("brick", "AHU")).unwrap(),
+            aliases
+        );
+
+        let desc = brick.class_desc(&Curie::new("brick", "AHU")).unwrap();
+        assert_eq!(desc.name, "Air_Handling_Unit");
+        assert_eq!(desc.aliases, aliases);
+        assert!(desc
+            .equivalent_classes
+            .contains(&Curie::new("brick", "AHU")));
     }
 }
@@ -8,7 +8,7 @@ use crate::namespaces::PrefixNamespaceMap;
 
 /// Compact IRI
 #[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
-#[derive(Default, Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[derive(Default, Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[serde(rename_all = "camelCase")]
 pub struct Curie {
     pub prefix: String,
@@ -14,4 +14,37 @@ pub struct BrickEntity {
     pub super_classes: Vec<Curie>,
     pub tags: Vec<String>,
     pub properties: Vec<BrickProperty>,
+    pub deprecation: Option<Deprecation>,
+    /// Classes declared with `brick:aliasOf` as aliases of this class.
+    pub aliases: Vec<Curie>,
+    /// Classes declared equivalent with `owl:equivalentClass`.
+    pub equivalent_classes: Vec<Curie>,
+    /// The substances, or media, declared with `brick:hasSubstance`.
+    pub substances: Vec<Curie>,
+    /// The quantities declared with `brick:hasQuantity`.
+    pub quantities: Vec<Curie>,
+}
+
+/// Deprecation details of a class marked with `owl:deprecated`.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Deprecation {
+    /// The Brick version that deprecated the class.
+    pub version: String,
+    /// How to migrate away from the class.
+    pub message: String,
+    /// The class that replaces it, if any.
+    pub replaced_by: Option<Curie>,
+    /// The `owl:deprecated` value when it isn't a boolean.
+    pub malformed_flag: Option<String>,
+}
+
+/// A deprecated class with its deprecation details.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeprecatedClass {
+    pub class: Curie,
+    pub deprecation: Deprecation,
 }
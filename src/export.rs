@@ -0,0 +1,776 @@
+// Copyright (C) 2025 Radu Racariu.
+
+//!
+//! Serialize model graphs to Turtle, N-Triples, N-Quads, JSON-LD and RDF/XML.
+//!
+//! The output is deterministic: subjects, predicates and objects are sorted and
+//! blank nodes are labelled from the subject and predicate they hang off, so that
+//! models kept under version control produce clean diffs.
+//!
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sophia::inmem::graph::FastGraph;
+use sophia::iri::Iri;
+use sophia::turtle::serializer::{
+    nq::NqSerializer,
+    nt::NtSerializer,
+    turtle::{TurtleConfig, TurtleSerializer},
+};
+use sophia_api::{
+    graph::{Graph, MutableGraph},
+    prefix::Prefix,
+    serializer::{QuadSerializer, Stringifier, TripleSerializer},
+    term::{BnodeId, SimpleTerm, Term},
+    triple::Triple,
+    MownStr,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{curie::Curie, namespaces::PrefixNamespaceMap};
+
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+const RDF_NS: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+
+/// The formats a model can be written to.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModelFormat {
+    #[default]
+    Turtle,
+    NTriples,
+    NQuads,
+    /// JSON-LD compacted with a context of the prefixes and Brick properties used
+    /// by the model.
+    JsonLd,
+    RdfXml,
+}
+
+type Subjects = BTreeMap<SimpleTerm<'static>, Vec<(SimpleTerm<'static>, SimpleTerm<'static>)>>;
+
+/// Write a graph in the given format, using the prefixes for readable CURIEs.
+/// The object properties are the ones whose JSON-LD values are IRIs.
+pub(crate) fn serialize(
+    graph: &FastGraph,
+    prefixes: &PrefixNamespaceMap,
+    object_properties: &[Curie],
+    format: ModelFormat,
+) -> Result<String> {
+    let graph = &relabel_blank_nodes(graph, prefixes)?;
+
+    match format {
+        ModelFormat::Turtle => turtle(graph, prefixes),
+        ModelFormat::NTriples => Ok(sorted_lines(
+            &NtSerializer::new_stringifier()
+                .serialize_graph(graph)?
+                .to_string(),
+        )),
+        ModelFormat::NQuads => Ok(sorted_lines(
+            &NqSerializer::new_stringifier()
+                .serialize_dataset(&graph.as_dataset())?
+                .to_string(),
+        )),
+        ModelFormat::JsonLd => json_ld(graph, prefixes, object_properties),
+        ModelFormat::RdfXml => rdf_xml(graph, prefixes),
+    }
+}
+
+/// A copy of the graph whose blank nodes are labelled from the subject and the
+/// predicate they hang off, such as `bldg_Floor1_area`, so that the labels don't
+/// depend on the order the graph was edited in. Blank nodes sharing a subject
+/// and predicate are numbered in the order of their own triples.
+fn relabel_blank_nodes(graph: &FastGraph, prefixes: &PrefixNamespaceMap) -> Result<FastGraph> {
+    let subjects = subjects(graph)?;
+
+    let mut labels = BTreeMap::<SimpleTerm<'static>, String>::new();
+    let mut used = BTreeSet::<String>::new();
+
+    let mut assign = |bnode: &SimpleTerm<'static>, base: String, labels: &mut BTreeMap<_, _>| {
+        let mut label = base.clone();
+        let mut n = 1;
+        while used.contains(&label) {
+            n += 1;
+            label = format!("{base}_{n}");
+        }
+        used.insert(label.clone());
+        labels.insert(bnode.clone(), label);
+    };
+
+    // Nested blank nodes get their label once the node using them has one.
+    loop {
+        let mut found = Vec::new();
+
+        for (subject, properties) in &subjects {
+            let owner = match labels.get(subject) {
+                Some(label) => label.clone(),
+                None if subject.is_blank_node() => continue,
+                None => compact(subject, prefixes),
+            };
+
+            for (predicate, object) in properties {
+                if object.is_blank_node() && !labels.contains_key(object) {
+                    let base = sanitize_label(&format!(
+                        "{owner}_{}",
+                        local_name(&compact(predicate, prefixes))
+                    ));
+                    found.push((base, signature(object, &subjects, prefixes), object));
+                }
+            }
+        }
+
+        if found.is_empty() {
+            break;
+        }
+
+        found.sort();
+        for (base, _, bnode) in found {
+            if !labels.contains_key(bnode) {
+                assign(bnode, base, &mut labels);
+            }
+        }
+    }
+
+    // Blank nodes not reachable from a named node.
+    let mut orphans = subjects
+        .keys()
+        .filter(|subject| subject.is_blank_node() && !labels.contains_key(*subject))
+        .map(|subject| (signature(subject, &subjects, prefixes), subject))
+        .collect::<Vec<_>>();
+    orphans.sort();
+    for (_, bnode) in orphans {
+        assign(bnode, "b".into(), &mut labels);
+    }
+
+    let relabel = |term: &SimpleTerm<'static>| match labels.get(term) {
+        Some(label) => SimpleTerm::BlankNode(BnodeId::new_unchecked(MownStr::from(label.clone()))),
+        None => term.clone(),
+    };
+
+    let mut relabelled = FastGraph::new();
+    for (subject, properties) in &subjects {
+        for (predicate, object) in properties {
+            relabelled.insert(relabel(subject), predicate.clone(), relabel(object))?;
+        }
+    }
+
+    Ok(relabelled)
+}
+
+/// The triples of a blank node, with the blank nodes they use left anonymous.
+fn signature(
+    bnode: &SimpleTerm<'static>,
+    subjects: &Subjects,
+    prefixes: &PrefixNamespaceMap,
+) -> Vec<(String, String)> {
+    let term = |term: &SimpleTerm| {
+        if term.is_literal() {
+            format!("{term:?}")
+        } else if term.is_blank_node() {
+            "_".into()
+        } else {
+            compact(term, prefixes)
+        }
+    };
+
+    subjects
+        .get(bnode)
+        .into_iter()
+        .flatten()
+        .map(|(predicate, object)| (term(predicate), term(object)))
+        .collect()
+}
+
+/// The local name of a CURIE or an IRI.
+fn local_name(name: &str) -> &str {
+    name.rsplit([':', '#', '/']).next().unwrap_or(name)
+}
+
+/// A blank node label of letters, digits and `_`, starting with a letter.
+fn sanitize_label(text: &str) -> String {
+    let label = text
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+
+    if label.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        label
+    } else {
+        format!("b{label}")
+    }
+}
+
+fn turtle(graph: &FastGraph, prefixes: &PrefixNamespaceMap) -> Result<String> {
+    let prefix_map = used_prefixes(graph, prefixes)?
+        .into_iter()
+        .filter_map(|(prefix, ns)| {
+            Some((
+                Prefix::new(Box::<str>::from(prefix)).ok()?,
+                Iri::new(Box::<str>::from(ns)).ok()?,
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let config = TurtleConfig::new()
+        .with_pretty(true)
+        .with_own_prefix_map(prefix_map);
+
+    Ok(TurtleSerializer::new_stringifier_with_config(config)
+        .serialize_graph(graph)?
+        .to_string())
+}
+
+fn json_ld(
+    graph: &FastGraph,
+    prefixes: &PrefixNamespaceMap,
+    object_properties: &[Curie],
+) -> Result<String> {
+    let subjects = subjects(graph)?;
+
+    let mut context = used_prefixes(graph, prefixes)?
+        .into_iter()
+        .map(|(prefix, ns)| (prefix, Value::String(ns)))
+        .collect::<Map<_, _>>();
+
+    let writer = JsonLd {
+        terms: json_ld_terms(&subjects, &mut context, object_properties, prefixes),
+        references: bnode_references(&subjects),
+        subjects: &subjects,
+        prefixes,
+    };
+
+    // Blank nodes are embedded at most once, those on a cycle of blank nodes
+    // only referenced by each other become top level nodes.
+    let mut visited = BTreeSet::new();
+    let mut nodes = Vec::new();
+    for top_level in [true, false] {
+        for subject in subjects.keys() {
+            if is_embedded(subject, &writer.references) != top_level && !visited.contains(subject) {
+                visited.insert(subject.clone());
+                nodes.push(Value::Object(writer.node(subject, true, &mut visited)));
+            }
+        }
+    }
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "@context": context,
+        "@graph": nodes,
+    }))?)
+}
+
+/// Define a term for each predicate with a prefix, named after its local name
+/// unless that clashes with a prefix or another predicate. Terms of object
+/// properties have `@type: @id`, so their values compact to plain IRIs.
+fn json_ld_terms(
+    subjects: &Subjects,
+    context: &mut Map<String, Value>,
+    object_properties: &[Curie],
+    prefixes: &PrefixNamespaceMap,
+) -> BTreeMap<SimpleTerm<'static>, (String, bool)> {
+    let predicates = subjects
+        .values()
+        .flatten()
+        .map(|(predicate, _)| predicate)
+        .filter(|predicate| !Term::eq(*predicate, sophia_api::ns::rdf::type_))
+        .filter_map(|predicate| {
+            Curie::from_term(predicate, prefixes)
+                .ok()
+                .map(|curie| (predicate.clone(), curie))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut names = BTreeMap::<&str, usize>::new();
+    for curie in predicates.values() {
+        *names.entry(curie.local_name.as_str()).or_default() += 1;
+    }
+
+    let mut terms = BTreeMap::new();
+    for (predicate, curie) in &predicates {
+        let name =
+            if names[curie.local_name.as_str()] == 1 && !context.contains_key(&curie.local_name) {
+                curie.local_name.clone()
+            } else {
+                curie.to_string()
+            };
+
+        let is_id = object_properties.contains(curie);
+
+        let mut definition = Map::from_iter([("@id".into(), Value::String(curie.to_string()))]);
+        if is_id {
+            definition.insert("@type".into(), Value::String("@id".into()));
+        }
+        context.insert(name.clone(), Value::Object(definition));
+
+        terms.insert(predicate.clone(), (name, is_id));
+    }
+
+    terms
+}
+
+struct JsonLd<'a> {
+    subjects: &'a Subjects,
+    references: BTreeMap<SimpleTerm<'static>, usize>,
+    /// The term of each predicate and whether its values are IRIs.
+    terms: BTreeMap<SimpleTerm<'static>, (String, bool)>,
+    prefixes: &'a PrefixNamespaceMap,
+}
+
+impl JsonLd<'_> {
+    fn node(
+        &self,
+        subject: &SimpleTerm<'static>,
+        top_level: bool,
+        visited: &mut BTreeSet<SimpleTerm<'static>>,
+    ) -> Map<String, Value> {
+        let mut node = Map::new();
+
+        if top_level {
+            node.insert("@id".into(), Value::String(compact(subject, self.prefixes)));
+        }
+
+        let mut values = BTreeMap::<String, Vec<Value>>::new();
+
+        for (predicate, object) in self.subjects.get(subject).into_iter().flatten() {
+            if Term::eq(predicate, sophia_api::ns::rdf::type_) && object.is_iri() {
+                values
+                    .entry("@type".into())
+                    .or_default()
+                    .push(Value::String(compact(object, self.prefixes)));
+                continue;
+            }
+
+            let (key, is_id) = match self.terms.get(predicate) {
+                Some((name, is_id)) => (name.clone(), *is_id),
+                None => (compact(predicate, self.prefixes), false),
+            };
+
+            let value = if is_embedded(object, &self.references) && visited.insert(object.clone()) {
+                Value::Object(self.node(object, false, visited))
+            } else if object.is_literal() {
+                json_ld_literal(object, self.prefixes)
+            } else if is_id {
+                Value::String(compact(object, self.prefixes))
+            } else {
+                json!({ "@id": compact(object, self.prefixes) })
+            };
+
+            values.entry(key).or_default().push(value);
+        }
+
+        for (key, mut value) in values {
+            node.insert(
+                key,
+                if value.len() == 1 {
+                    value.remove(0)
+                } else {
+                    Value::Array(value)
+                },
+            );
+        }
+
+        node
+    }
+}
+
+fn json_ld_literal(literal: &SimpleTerm, prefixes: &PrefixNamespaceMap) -> Value {
+    let value = literal
+        .lexical_form()
+        .map_or(String::new(), |v| v.to_string());
+
+    if let Some(language) = literal.language_tag() {
+        json!({ "@value": value, "@language": language.as_str() })
+    } else {
+        match literal.datatype() {
+            Some(datatype) if datatype.as_str() != XSD_STRING => json!({
+                "@value": value,
+                "@type": Curie::from_iri(datatype.clone(), prefixes)
+                    .map(|curie| curie.to_string())
+                    .unwrap_or_else(|_| datatype.as_str().to_string()),
+            }),
+            _ => Value::String(value),
+        }
+    }
+}
+
+fn rdf_xml(graph: &FastGraph, prefixes: &PrefixNamespaceMap) -> Result<String> {
+    let subjects = subjects(graph)?;
+
+    let mut namespaces = used_prefixes(graph, prefixes)?;
+    if !namespaces.iter().any(|(_, ns)| ns == RDF_NS) {
+        namespaces.push(("rdf".into(), RDF_NS.into()));
+    }
+
+    // Predicates must be qualified names, their namespaces without a
+    // prefix get generated ones.
+    let mut qname = |predicate: &SimpleTerm| -> Result<String> {
+        let iri = predicate.iri().map_or(String::new(), |iri| iri.to_string());
+        let (ns, local_name) = split_qname(&iri)
+            .ok_or_else(|| anyhow!("{iri} can't be written as an RDF/XML property"))?;
+
+        let prefix = match namespaces.iter().find(|(_, known)| known == ns) {
+            Some((prefix, _)) => prefix.clone(),
+            None => {
+                let mut n = namespaces.len();
+                while namespaces
+                    .iter()
+                    .any(|(known, _)| *known == format!("ns{n}"))
+                {
+                    n += 1;
+                }
+                let prefix = format!("ns{n}");
+                namespaces.push((prefix.clone(), ns.to_string()));
+                prefix
+            }
+        };
+
+        Ok(format!("{prefix}:{local_name}"))
+    };
+
+    let mut body = String::new();
+
+    for (subject, properties) in &subjects {
+        body.push_str(&format!(
+            "  <rdf:Description {}>\n",
+            node_attr(subject, "about")
+        ));
+
+        for (predicate, object) in properties {
+            let name = qname(predicate)?;
+
+            if object.is_literal() {
+                let value = escape_xml(
+                    &object
+                        .lexical_form()
+                        .map_or(String::new(), |v| v.to_string()),
+                );
+
+                let attr = match (object.language_tag(), object.datatype()) {
+                    (Some(language), _) => format!(" xml:lang=\"{}\"", language.as_str()),
+                    (None, Some(datatype)) if datatype.as_str() != XSD_STRING => {
+                        format!(" rdf:datatype=\"{}\"", escape_xml(datatype.as_str()))
+                    }
+                    _ => String::new(),
+                };
+
+                body.push_str(&format!("    <{name}{attr}>{value}</{name}>\n"));
+            } else {
+                body.push_str(&format!(
+                    "    <{name} {}/>\n",
+                    node_attr(object, "resource")
+                ));
+            }
+        }
+
+        body.push_str("  </rdf:Description>\n");
+    }
+
+    namespaces.sort();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<rdf:RDF");
+    for (prefix, ns) in &namespaces {
+        xml.push_str(&format!("\n    xmlns:{prefix}=\"{}\"", escape_xml(ns)));
+    }
+    xml.push_str(">\n");
+    xml.push_str(&body);
+    xml.push_str("</rdf:RDF>\n");
+
+    Ok(xml)
+}
+
+/// Split an IRI at its last `#` or `/` in a namespace and a local name. RDF/XML
+/// property elements need the local name to be an XML NCName, so when it isn't,
+/// such as `2nd` in `urn:example#2nd`, the namespace is extended up to its
+/// longest NCName suffix, here `urn:example#2` and `nd`, which then gets a
+/// generated prefix.
+fn split_qname(iri: &str) -> Option<(&str, &str)> {
+    let at = iri.rfind(['#', '/'])? + 1;
+    if is_ncname(&iri[at..]) {
+        return Some(iri.split_at(at));
+    }
+
+    iri[at..]
+        .char_indices()
+        .map(|(i, _)| at + i)
+        .find(|&i| is_ncname(&iri[i..]))
+        .map(|i| iri.split_at(i))
+}
+
+fn is_ncname(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// The `rdf:about`, or `rdf:resource`, attribute of an IRI or the `rdf:nodeID` of a blank node.
+fn node_attr(term: &SimpleTerm, iri_attr: &str) -> String {
+    match (term.iri(), term.bnode_id()) {
+        (Some(iri), _) => format!("rdf:{iri_attr}=\"{}\"", escape_xml(iri.as_str())),
+        (None, Some(id)) => format!("rdf:nodeID=\"{}\"", escape_xml(id.as_str())),
+        _ => String::new(),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The triples grouped by subject, sorted.
+fn subjects(graph: &FastGraph) -> Result<Subjects> {
+    let mut subjects = Subjects::new();
+
+    for triple in graph.triples() {
+        let [s, p, o] = triple?.spo().map(|term| term.into_term::<SimpleTerm>());
+        subjects.entry(s).or_default().push((p, o));
+    }
+
+    for properties in subjects.values_mut() {
+        properties.sort();
+    }
+
+    Ok(subjects)
+}
+
+/// How many times each blank node is used as an object.
+fn bnode_references(subjects: &Subjects) -> BTreeMap<SimpleTerm<'static>, usize> {
+    let mut references = BTreeMap::new();
+
+    for (_, object) in subjects.values().flatten() {
+        if object.is_blank_node() {
+            *references.entry(object.clone()).or_default() += 1;
+        }
+    }
+
+    references
+}
+
+/// A blank node used once as an object is written inside the node using it.
+fn is_embedded(
+    term: &SimpleTerm<'static>,
+    references: &BTreeMap<SimpleTerm<'static>, usize>,
+) -> bool {
+    references.get(term) == Some(&1)
+}
+
+/// The CURIE of an IRI when its namespace has a prefix, the IRI otherwise.
+fn compact(term: &SimpleTerm, prefixes: &PrefixNamespaceMap) -> String {
+    if let Some(id) = term.bnode_id() {
+        return format!("_:{}", id.as_str());
+    }
+
+    Curie::from_term(term, prefixes)
+        .map(|curie| curie.to_string())
+        .unwrap_or_else(|_| term.iri().map_or(String::new(), |iri| iri.to_string()))
+}
+
+/// The prefixes of the namespaces used by the graph IRIs and literal datatypes, sorted.
+fn used_prefixes(
+    graph: &FastGraph,
+    prefixes: &PrefixNamespaceMap,
+) -> Result<Vec<(String, String)>> {
+    let mut used = BTreeMap::new();
+
+    for triple in graph.triples() {
+        for term in triple?.spo() {
+            let iri = match (term.iri(), term.datatype()) {
+                (Some(iri), _) => iri,
+                (None, Some(datatype)) if datatype.as_str() != XSD_STRING => datatype,
+                _ => continue,
+            };
+
+            if let Ok(curie) = Curie::from_iri(iri, prefixes) {
+                if let Some(ns) = prefixes.get_ns(&curie.prefix) {
+                    used.insert(curie.prefix, ns.as_str().to_string());
+                }
+            }
+        }
+    }
+
+    Ok(used.into_iter().collect())
+}
+
+fn sorted_lines(text: &str) -> String {
+    let mut lines = text.lines().collect::<Vec<_>>();
+    lines.sort();
+    lines.dedup();
+
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{brick::test::test_brick, model::BrickModel};
+
+    #[test]
+    fn escapes_xml() {
+        assert_eq!(
+            escape_xml(r#"<a href="x">&</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn splits_qnames() {
+        assert_eq!(
+            split_qname("https://brickschema.org/schema/Brick#area"),
+            Some(("https://brickschema.org/schema/Brick#", "area"))
+        );
+        assert_eq!(split_qname("urn:example#p2"), Some(("urn:example#", "p2")));
+        assert_eq!(
+            split_qname("urn:example/x.1-y"),
+            Some(("urn:example/", "x.1-y"))
+        );
+        assert_eq!(
+            split_qname("urn:example#2nd"),
+            Some(("urn:example#2", "nd"))
+        );
+        assert_eq!(split_qname("urn:example#123"), None);
+        assert_eq!(split_qname("urn:example/"), None);
+        assert_eq!(split_qname("urn:a#b/c.d"), Some(("urn:a#b/", "c.d")));
+    }
+
+    #[test]
+    fn prefixes_digit_leading_properties() {
+        let prefixes = PrefixNamespaceMap::new(&[("bldg".into(), "urn:example#".into())].into());
+
+        let mut graph = FastGraph::new();
+        graph
+            .insert(
+                Iri::new_unchecked("urn:example#AHU1"),
+                Iri::new_unchecked("urn:example#2nd"),
+                "x",
+            )
+            .unwrap();
+
+        let rdf_xml = rdf_xml(&graph, &prefixes).unwrap();
+        assert!(rdf_xml.contains(r#"xmlns:ns2="urn:example#2""#));
+        assert!(rdf_xml.contains("<ns2:nd>x</ns2:nd>"));
+        assert!(!rdf_xml.contains("bldg:2nd"));
+    }
+
+    #[test]
+    fn embeds_blank_node_cycles_once() {
+        let prefixes = PrefixNamespaceMap::new(&[("bldg".into(), "urn:example#".into())].into());
+        let bnode = |id| BnodeId::new_unchecked(id);
+        let next = Iri::new_unchecked("urn:example#next");
+
+        let mut graph = FastGraph::new();
+        graph.insert(bnode("a"), next, bnode("b")).unwrap();
+        graph.insert(bnode("b"), next, bnode("a")).unwrap();
+        graph.insert(bnode("c"), next, bnode("c")).unwrap();
+
+        let json_ld: serde_json::Value =
+            serde_json::from_str(&json_ld(&graph, &prefixes, &[]).unwrap()).unwrap();
+        assert_eq!(
+            json_ld["@graph"],
+            json!([
+                { "@id": "_:a", "next": { "next": { "@id": "_:a" } } },
+                { "@id": "_:c", "next": { "@id": "_:c" } },
+            ])
+        );
+    }
+
+    #[test]
+    fn sanitizes_labels() {
+        assert_eq!(sanitize_label("bldg:Floor1_area"), "bldg_Floor1_area");
+        assert_eq!(sanitize_label("1st"), "b1st");
+    }
+
+    #[test]
+    fn sorts_lines() {
+        assert_eq!(
+            sorted_lines("<b> <p> <o> .\n<a> <p> <o> .\n"),
+            "<a> <p> <o> .\n<b> <p> <o> .\n"
+        );
+        assert_eq!(sorted_lines(""), "");
+    }
+
+    #[test]
+    fn exports_models() {
+        let brick = test_brick();
+        let class = |name| Curie::new("brick", name);
+
+        let build = |entities: &[(&str, &str)]| {
+            let mut model = BrickModel::new(brick, "bldg", "urn:example#").unwrap();
+            for (id, name) in entities {
+                model.add_entity(id, &Curie::new("brick", name)).unwrap();
+            }
+            model
+        };
+
+        let mut model = build(&[("VAV1", "VAV"), ("AHU1", "AHU"), ("Floor1", "Floor")]);
+        model.relate("AHU1", &class("feeds"), "VAV1").unwrap();
+        model
+            .set_property(
+                "Floor1",
+                &class("area"),
+                "250.5",
+                Some(&Curie::new("unit", "M2")),
+            )
+            .unwrap();
+
+        let turtle = model.export(ModelFormat::Turtle).unwrap();
+        assert!(turtle.contains("PREFIX bldg: <urn:example#>"));
+        assert!(turtle.contains("bldg:AHU1 a brick:AHU"));
+        assert!(turtle.contains("brick:hasUnit unit:M2"));
+        assert!(!turtle.contains("PREFIX owl:"));
+        assert!(turtle.find("bldg:AHU1") < turtle.find("bldg:Floor1"));
+        assert!(turtle.find("bldg:Floor1") < turtle.find("bldg:VAV1 a"));
+
+        let n_triples = model.export(ModelFormat::NTriples).unwrap();
+        assert_eq!(n_triples.lines().count(), 7);
+        assert!(n_triples
+            .starts_with("<urn:example#AHU1> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type>"));
+        assert_eq!(model.export(ModelFormat::NQuads).unwrap(), n_triples);
+
+        let json_ld: serde_json::Value =
+            serde_json::from_str(&model.export(ModelFormat::JsonLd).unwrap()).unwrap();
+        assert_eq!(
+            json_ld["@context"]["brick"],
+            "https://brickschema.org/schema/Brick#"
+        );
+        assert_eq!(
+            json_ld["@context"]["feeds"],
+            json!({ "@id": "brick:feeds", "@type": "@id" })
+        );
+        assert_eq!(
+            json_ld["@context"]["value"],
+            json!({ "@id": "brick:value" })
+        );
+        assert_eq!(json_ld["@graph"][0]["@id"], "bldg:AHU1");
+        assert_eq!(json_ld["@graph"][0]["feeds"], "bldg:VAV1");
+        assert_eq!(json_ld["@graph"][1]["area"]["hasUnit"], "unit:M2");
+        assert_eq!(json_ld["@graph"][1]["area"]["value"]["@value"], "250.5");
+
+        let rdf_xml = model.export(ModelFormat::RdfXml).unwrap();
+        assert!(rdf_xml.contains(r#"xmlns:brick="https://brickschema.org/schema/Brick#""#));
+        assert!(rdf_xml.contains(r#"<brick:feeds rdf:resource="urn:example#VAV1"/>"#));
+        assert!(rdf_xml.contains(r#"<brick:area rdf:nodeID="bldg_Floor1_area"/>"#));
+        assert!(rdf_xml.trim_end().ends_with("</rdf:RDF>"));
+
+        let mut a = build(&[("VAV1", "VAV"), ("AHU1", "AHU"), ("Floor1", "Floor")]);
+        let mut b = build(&[("AHU1", "AHU"), ("Floor1", "Floor"), ("VAV1", "VAV")]);
+        a.set_property("Floor1", &class("area"), "100", None)
+            .unwrap();
+        a.set_property("AHU1", &class("azimuth"), "90", None)
+            .unwrap();
+        a.set_property("Floor1", &class("area"), "250.5", None)
+            .unwrap();
+        b.set_property("AHU1", &class("azimuth"), "90", None)
+            .unwrap();
+        b.set_property("Floor1", &class("area"), "250.5", None)
+            .unwrap();
+        for format in [
+            ModelFormat::Turtle,
+            ModelFormat::NTriples,
+            ModelFormat::JsonLd,
+            ModelFormat::RdfXml,
+        ] {
+            assert_eq!(a.export(format).unwrap(), b.export(format).unwrap());
+        }
+    }
+}
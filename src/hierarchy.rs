@@ -0,0 +1,268 @@
+// Copyright (C) 2025 Radu Racariu.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sophia::inmem::graph::FastGraph;
+use sophia_api::{
+    graph::Graph,
+    ns::{owl, rdf, rdfs},
+    term::matcher::Any,
+    triple::Triple,
+};
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use crate::{curie::Curie, namespaces::PrefixNamespaceMap};
+
+/// A class reached by walking the `rdfs:subClassOf` hierarchy.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassRelative {
+    pub class: Curie,
+    /// Number of `rdfs:subClassOf` steps on the shortest path.
+    pub distance: u32,
+    /// Derivation paths from the starting class to this class, both included.
+    /// The first path is always a shortest one.
+    pub paths: Vec<Vec<Curie>>,
+}
+
+/// A node of the class hierarchy tree.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassNode {
+    pub curie: Curie,
+    pub label: String,
+    pub children: Vec<ClassNode>,
+    pub deprecated: bool,
+    pub tag_count: u32,
+}
+
+/// Precomputed `rdfs:subClassOf` index, used for fast hierarchy queries.
+#[derive(Default, Debug)]
+pub(crate) struct ClassHierarchy {
+    ids: HashMap<Curie, usize>,
+    classes: Vec<Curie>,
+    parents: Vec<Vec<usize>>,
+    children: Vec<Vec<usize>>,
+    /// For each class, all of its ancestors, itself included, with their shortest distance.
+    ancestors: Vec<HashMap<usize, u32>>,
+}
+
+impl ClassHierarchy {
+    pub(crate) fn new(graph: &FastGraph, prefixes: &PrefixNamespaceMap) -> Result<Self> {
+        let mut hierarchy = ClassHierarchy::default();
+
+        for triple in graph.triples_matching(Any, [&rdfs::subClassOf], Any) {
+            let triple = triple?;
+
+            let (Ok(sub), Ok(sup)) = (
+                Curie::from_term(triple.s(), prefixes),
+                Curie::from_term(triple.o(), prefixes),
+            ) else {
+                continue;
+            };
+
+            let sub = hierarchy.insert(sub);
+            let sup = hierarchy.insert(sup);
+
+            if !hierarchy.parents[sub].contains(&sup) {
+                hierarchy.parents[sub].push(sup);
+                hierarchy.children[sup].push(sub);
+            }
+        }
+
+        // Classes without any superclass or subclass are known too.
+        for triple in graph.triples_matching(Any, [rdf::type_], [rdfs::Class, owl::Class]) {
+            if let Ok(class) = Curie::from_term(triple?.s(), prefixes) {
+                hierarchy.insert(class);
+            }
+        }
+
+        hierarchy.ancestors = (0..hierarchy.classes.len())
+            .map(|id| {
+                let mut ancestors = HashMap::from([(id, 0)]);
+                let mut queue = VecDeque::from([id]);
+
+                while let Some(cur) = queue.pop_front() {
+                    let distance = ancestors[&cur] + 1;
+                    for &parent in &hierarchy.parents[cur] {
+                        if let Entry::Vacant(entry) = ancestors.entry(parent) {
+                            entry.insert(distance);
+                            queue.push_back(parent);
+                        }
+                    }
+                }
+
+                ancestors
+            })
+            .collect();
+
+        Ok(hierarchy)
+    }
+
+    fn insert(&mut self, class: Curie) -> usize {
+        *self.ids.entry(class).or_insert_with_key(|class| {
+            self.classes.push(class.clone());
+            self.parents.push(Vec::new());
+            self.children.push(Vec::new());
+            self.classes.len() - 1
+        })
+    }
+
+    /// The classes reached from a class by walking up or down the hierarchy, closest
+    /// first, each with a shortest path, or with every simple path when `all_paths`
+    /// is set.
+    pub(crate) fn relatives(
+        &self,
+        class: &Curie,
+        upward: bool,
+        all_paths: bool,
+    ) -> Vec<ClassRelative> {
+        let Some(&start) = self.ids.get(class) else {
+            return Vec::new();
+        };
+        let next = if upward {
+            &self.parents
+        } else {
+            &self.children
+        };
+
+        // The class each relative is first reached from, on a shortest path.
+        let mut reached_from = HashMap::from([(start, start)]);
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(cur) = queue.pop_front() {
+            for &relative in &next[cur] {
+                if let Entry::Vacant(entry) = reached_from.entry(relative) {
+                    entry.insert(cur);
+                    order.push(relative);
+                    queue.push_back(relative);
+                }
+            }
+        }
+
+        let mut paths = HashMap::<usize, Vec<Vec<usize>>>::new();
+
+        if all_paths {
+            // Depth first, never revisiting a class already on the current path.
+            let mut path = vec![start];
+            let mut on_path = vec![false; self.classes.len()];
+            on_path[start] = true;
+            let mut stack = vec![next[start].iter()];
+
+            while let Some(relatives) = stack.last_mut() {
+                match relatives.next() {
+                    Some(&relative) if !on_path[relative] => {
+                        path.push(relative);
+                        paths.entry(relative).or_default().push(path.clone());
+                        on_path[relative] = true;
+                        stack.push(next[relative].iter());
+                    }
+                    Some(_) => {}
+                    None => {
+                        stack.pop();
+                        if let Some(last) = path.pop() {
+                            on_path[last] = false;
+                        }
+                    }
+                }
+            }
+
+            for paths in paths.values_mut() {
+                paths.sort_by_key(|path| path.len());
+            }
+        } else {
+            for &id in &order {
+                let mut path = vec![id];
+                let mut cur = id;
+                while cur != start {
+                    cur = reached_from[&cur];
+                    path.push(cur);
+                }
+                path.reverse();
+                paths.insert(id, vec![path]);
+            }
+        }
+
+        order
+            .into_iter()
+            .map(|id| {
+                let paths = paths.remove(&id).unwrap_or_default();
+
+                ClassRelative {
+                    class: self.classes[id].clone(),
+                    distance: paths[0].len() as u32 - 1,
+                    paths: paths
+                        .into_iter()
+                        .map(|path| {
+                            path.into_iter()
+                                .map(|id| self.classes[id].clone())
+                                .collect()
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn is_subclass_of(&self, sub: &Curie, sup: &Curie) -> bool {
+        match (self.ids.get(sub), self.ids.get(sup)) {
+            (Some(sub), Some(sup)) => self.ancestors[*sub].contains_key(sup),
+            _ => false,
+        }
+    }
+
+    /// Ancestors shared by all the classes, with the sum of their distances to them.
+    fn common(&self, classes: &[Curie]) -> Vec<(usize, u32)> {
+        let Some(ids) = classes
+            .iter()
+            .map(|class| self.ids.get(class).copied())
+            .collect::<Option<Vec<usize>>>()
+        else {
+            return Vec::new();
+        };
+
+        let Some((first, rest)) = ids.split_first() else {
+            return Vec::new();
+        };
+
+        let mut common = self.ancestors[*first]
+            .iter()
+            .filter_map(|(ancestor, distance)| {
+                rest.iter()
+                    .map(|id| self.ancestors[*id].get(ancestor))
+                    .sum::<Option<u32>>()
+                    .map(|sum| (*ancestor, distance + sum))
+            })
+            .collect::<Vec<(usize, u32)>>();
+
+        common.sort_by(|(a, a_dist), (b, b_dist)| {
+            a_dist
+                .cmp(b_dist)
+                .then_with(|| self.classes[*a].cmp(&self.classes[*b]))
+        });
+
+        common
+    }
+
+    pub(crate) fn common_ancestors(&self, classes: &[Curie]) -> Vec<Curie> {
+        self.common(classes)
+            .into_iter()
+            .map(|(id, _)| self.classes[id].clone())
+            .collect()
+    }
+
+    pub(crate) fn lowest_common_ancestor(&self, classes: &[Curie]) -> Option<Curie> {
+        self.common(classes)
+            .first()
+            .map(|(id, _)| self.classes[*id].clone())
+    }
+
+    pub(crate) fn distance(&self, a: &Curie, b: &Curie) -> Option<u32> {
+        self.common(&[a.clone(), b.clone()])
+            .first()
+            .map(|(_, distance)| *distance)
+    }
+}
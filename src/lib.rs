@@ -7,8 +7,18 @@
 pub mod brick;
 pub mod curie;
 pub mod entity;
+pub mod export;
+pub mod hierarchy;
+pub mod migration;
+pub mod model;
 pub mod namespaces;
 pub mod property;
+pub mod qudt;
+pub mod relationship;
+pub mod search;
+pub mod shape;
+pub mod suggest;
+pub mod tags;
 
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
@@ -0,0 +1,370 @@
+// Copyright (C) 2025 Radu Racariu.
+
+//!
+//! Migrate building models away from deprecated Brick classes and properties.
+//!
+
+use anyhow::{anyhow, Result};
+use rio_api::parser::TriplesParser;
+use serde::{Deserialize, Serialize};
+use sophia::inmem::graph::FastGraph;
+use sophia::iri::Iri;
+use sophia::turtle::serializer::turtle::{TurtleConfig, TurtleSerializer};
+use sophia_api::{
+    graph::{Graph, MutableGraph},
+    ns::rdf,
+    prefix::Prefix,
+    prelude::TripleParser,
+    serializer::{Stringifier, TripleSerializer},
+    term::{SimpleTerm, Term},
+    triple::Triple,
+};
+use sophia_rio::model::Trusted;
+use sophia_turtle::parser::turtle::TurtleParser;
+use std::collections::HashMap;
+
+use crate::{curie::Curie, entity::Deprecation, namespaces::PrefixNamespaceMap};
+
+/// The outcome of migrating a model.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    /// The migrated model, as Turtle.
+    pub model: String,
+    pub changes: Vec<MigrationChange>,
+    /// Deprecated usages that could not be migrated automatically.
+    pub warnings: Vec<String>,
+}
+
+/// What a migration change rewrote.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationKind {
+    /// The `rdf:type` of an entity.
+    #[default]
+    Class,
+    /// A property of an entity.
+    Property,
+}
+
+/// A single rewrite applied to a model entity.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationChange {
+    /// The migrated entity, as a CURIE when the model declares its prefix.
+    pub entity: String,
+    pub kind: MigrationKind,
+    pub from: Curie,
+    pub to: Curie,
+    pub message: String,
+}
+
+/// A mitigation rule that renames a property, `$this old ?v` becoming `$this new ?v`.
+#[derive(Debug)]
+pub(crate) struct PropertyRename {
+    pub(crate) from: Curie,
+    pub(crate) to: Curie,
+    pub(crate) message: String,
+}
+
+/// A mitigation rule that can't be applied automatically, reported when the
+/// model uses the deprecated term.
+#[derive(Debug)]
+pub(crate) struct UnsupportedRule {
+    pub(crate) deprecated: Curie,
+    pub(crate) message: String,
+}
+
+/// Parse a SPARQL `CONSTRUCT` mitigation rule that only renames a property.
+pub(crate) fn parse_rename_rule(construct: &str) -> Option<(Curie, Curie)> {
+    let (construct, pattern) = construct.split_once("WHERE")?;
+
+    let triple_of = |text: &str| -> Option<(String, String)> {
+        let body = text.split_once('{')?.1.split_once('}')?.0;
+        match body
+            .trim()
+            .trim_end_matches('.')
+            .split_whitespace()
+            .collect::<Vec<&str>>()[..]
+        {
+            ["$this", predicate, var] if var.starts_with('?') => {
+                Some((predicate.to_string(), var.to_string()))
+            }
+            _ => None,
+        }
+    };
+
+    let (to, to_var) = triple_of(construct)?;
+    let (from, from_var) = triple_of(pattern)?;
+
+    if to_var != from_var {
+        return None;
+    }
+
+    Some((
+        Curie::try_from(from.as_str()).ok()?,
+        Curie::try_from(to.as_str()).ok()?,
+    ))
+}
+
+/// Rewrite the model deprecated classes to their replacements and apply the property renames.
+/// Usages of terms with unsupported mitigation rules are reported as warnings.
+pub(crate) fn migrate(
+    model: &str,
+    prefixes: &PrefixNamespaceMap,
+    classes: &HashMap<Curie, Deprecation>,
+    renames: &[PropertyRename],
+    unsupported: &[UnsupportedRule],
+) -> Result<MigrationReport> {
+    let mut graph = FastGraph::new();
+
+    let mut parser = TurtleParser { base: None }.parse_str(model).0;
+
+    parser.parse_all(&mut |triple| {
+        graph.insert_triple(Trusted(triple))?;
+        anyhow::Ok(())
+    })?;
+
+    let model_prefixes = PrefixNamespaceMap::new(parser.prefixes());
+
+    let mut report = MigrationReport::default();
+    let mut migrated = FastGraph::new();
+
+    for triple in graph.triples() {
+        let [s, p, o] = triple?.spo().map(|term| term.into_term::<SimpleTerm>());
+
+        let entity = Curie::from_term(&s, &model_prefixes)
+            .map(|curie| curie.to_string())
+            .unwrap_or_else(|_| term_string(&s));
+
+        let mut predicate = p.clone();
+        let mut object = o.clone();
+
+        let used = if Term::eq(&p, rdf::type_) { &o } else { &p };
+        if let Ok(used) = Curie::from_term(used, prefixes) {
+            for rule in unsupported.iter().filter(|rule| rule.deprecated == used) {
+                report.warnings.push(format!(
+                    "{entity} uses {used}, whose mitigation rule can't be applied automatically. {}",
+                    rule.message
+                ));
+            }
+        }
+
+        if Term::eq(&p, rdf::type_) {
+            if let Some((from, deprecation)) = Curie::from_term(&o, prefixes)
+                .ok()
+                .and_then(|class| classes.get_key_value(&class))
+            {
+                match replacement(from, classes) {
+                    Some(to) => {
+                        object = iri_term(&to, prefixes)?;
+                        report.changes.push(MigrationChange {
+                            entity,
+                            kind: MigrationKind::Class,
+                            from: from.clone(),
+                            to,
+                            message: deprecation.message.clone(),
+                        });
+                    }
+                    None => report.warnings.push(format!(
+                        "{entity} is a deprecated {from} with no replacement. {}",
+                        deprecation.message
+                    )),
+                }
+            }
+        } else if let Some(rename) = Curie::from_term(&p, prefixes)
+            .ok()
+            .and_then(|property| renames.iter().find(|rename| rename.from == property))
+        {
+            predicate = iri_term(&rename.to, prefixes)?;
+            report.changes.push(MigrationChange {
+                entity,
+                kind: MigrationKind::Property,
+                from: rename.from.clone(),
+                to: rename.to.clone(),
+                message: rename.message.clone(),
+            });
+        }
+
+        migrated.insert(s, predicate, object)?;
+    }
+
+    report.changes.sort_by(|a, b| {
+        Ord::cmp(&a.entity, &b.entity)
+            .then_with(|| Ord::cmp(&a.from, &b.from))
+            .then_with(|| Ord::cmp(&a.to, &b.to))
+    });
+    report.warnings.sort();
+    report.warnings.dedup();
+
+    let mut prefix_map = parser
+        .prefixes()
+        .iter()
+        .filter_map(|(prefix, ns)| {
+            Some((
+                Prefix::new(Box::<str>::from(prefix.as_str())).ok()?,
+                Iri::new(Box::<str>::from(ns.as_str())).ok()?,
+            ))
+        })
+        .collect::<Vec<_>>();
+    prefix_map.sort_by(|a, b| Ord::cmp(a.0.as_str(), b.0.as_str()));
+
+    let config = TurtleConfig::new()
+        .with_pretty(true)
+        .with_own_prefix_map(prefix_map);
+
+    report.model = TurtleSerializer::new_stringifier_with_config(config)
+        .serialize_graph(&migrated)?
+        .to_string();
+
+    Ok(report)
+}
+
+/// Follow the `brick:isReplacedBy` chain up to a class that is not deprecated.
+fn replacement(class: &Curie, classes: &HashMap<Curie, Deprecation>) -> Option<Curie> {
+    let mut cur = class;
+    let mut seen = vec![class];
+
+    while let Some(next) = classes
+        .get(cur)
+        .and_then(|deprecation| deprecation.replaced_by.as_ref())
+    {
+        if seen.contains(&next) {
+            break;
+        }
+        seen.push(next);
+        cur = next;
+    }
+
+    (cur != class).then(|| cur.clone())
+}
+
+fn iri_term(curie: &Curie, prefixes: &PrefixNamespaceMap) -> Result<SimpleTerm<'static>> {
+    let ns = prefixes
+        .get_ns(&curie.prefix)
+        .ok_or_else(|| anyhow!("Missing prefix {}", curie.prefix))?;
+
+    Ok(ns.get(&curie.local_name)?.into_term())
+}
+
+fn term_string(term: &SimpleTerm) -> String {
+    if let Some(iri) = term.iri() {
+        iri.as_str().to_string()
+    } else if let Some(id) = term.bnode_id() {
+        format!("_:{}", id.as_str())
+    } else {
+        term.lexical_form().map_or(String::new(), |v| v.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brick::{test::test_brick, Brick};
+
+    #[test]
+    fn parses_rename_rules() {
+        let rule = r#"
+            CONSTRUCT {
+              $this brick:electricalFlow ?prop .
+            } WHERE {
+              $this brick:powerFlow ?prop .
+            }"#;
+
+        assert_eq!(
+            parse_rename_rule(rule),
+            Some((
+                Curie::new("brick", "powerFlow"),
+                Curie::new("brick", "electricalFlow")
+            ))
+        );
+
+        let rule = "CONSTRUCT { $this brick:a ?x } WHERE { $this brick:b ?y }";
+        assert_eq!(parse_rename_rule(rule), None);
+    }
+
+    #[test]
+    fn migrates_models() {
+        let brick = test_brick();
+
+        let report = brick
+            .migrate_model(
+                r#"
+                @prefix brick: <https://brickschema.org/schema/Brick#> .
+                @prefix bldg: <http://example.com/building#> .
+
+                bldg:ztsp a brick:Zone_Air_Temperature_Setpoint .
+                bldg:meter a brick:Electrical_Meter ;
+                    brick:powerFlow bldg:flow .
+                "#,
+            )
+            .unwrap();
+
+        assert_eq!(report.changes.len(), 2);
+
+        assert_eq!(report.changes[0].entity, "bldg:meter");
+        assert_eq!(report.changes[0].kind, MigrationKind::Property);
+        assert_eq!(report.changes[0].to, Curie::new("brick", "electricalFlow"));
+
+        assert_eq!(report.changes[1].entity, "bldg:ztsp");
+        assert_eq!(report.changes[1].kind, MigrationKind::Class);
+        assert_eq!(
+            report.changes[1].to,
+            Curie::new("brick", "Target_Zone_Air_Temperature_Setpoint")
+        );
+
+        assert!(report
+            .model
+            .contains("brick:Target_Zone_Air_Temperature_Setpoint"));
+        assert!(report.model.contains("brick:electricalFlow"));
+        assert!(!report.model.contains("brick:powerFlow"));
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn warns_about_unsupported_rules() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+
+            brick:oldArea owl:deprecated true ;
+                brick:deprecation [
+                    brick:deprecationMitigationMessage "Split oldArea in area and volume" ;
+                    brick:deprecationMitigationRule [ a sh:NodeShape ;
+                        sh:rule [ a sh:SPARQLRule ;
+                            sh:construct """
+                            CONSTRUCT {
+                              $this brick:area ?a .
+                              $this brick:volume ?v .
+                            } WHERE {
+                              $this brick:oldArea ?a .
+                            }""" ] ;
+                        sh:targetSubjectsOf brick:oldArea ] ] .
+            "#,
+        )
+        .unwrap();
+
+        let report = brick
+            .migrate_model(
+                r#"
+                @prefix brick: <https://brickschema.org/schema/Brick#> .
+                @prefix bldg: <http://example.com/building#> .
+
+                bldg:room brick:oldArea bldg:a1, bldg:a2 .
+                "#,
+            )
+            .unwrap();
+
+        assert!(report.changes.is_empty());
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("bldg:room"));
+        assert!(report.warnings[0].contains("brick:oldArea"));
+        assert!(report.warnings[0].contains("Split oldArea in area and volume"));
+        assert!(report.model.contains("brick:oldArea"));
+    }
+}
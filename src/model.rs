@@ -0,0 +1,711 @@
+// Copyright (C) 2025 Radu Racariu.
+
+//!
+//! Build Brick models: entities, their relationships and properties,
+//! checked against the ontology as they are added.
+//!
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sophia::inmem::graph::FastGraph;
+use sophia_api::{
+    graph::{Graph, MutableGraph},
+    ns::{rdf, Namespace},
+    term::{matcher::Any, BnodeId, IriRef, SimpleTerm, Term},
+    triple::Triple,
+    MownStr,
+};
+use std::{collections::HashMap, ops::Deref};
+
+use crate::{
+    brick::Brick,
+    curie::Curie,
+    export::{self, ModelFormat},
+    namespaces::PrefixNamespaceMap,
+};
+
+/// A Brick model, the instance triples of a building, kept in its own graph.
+///
+/// The entities are named in the model namespace, while classes, relationships
+/// and properties are CURIEs resolved with the ontology prefixes.
+///
+/// The model holds the ontology it is checked against, borrowed as a `&Brick`
+/// or shared, for instance as an `Rc<Brick>`.
+pub struct BrickModel<B> {
+    brick: B,
+    graph: FastGraph,
+    prefixes: PrefixNamespaceMap,
+    prefix: String,
+    next_value: usize,
+}
+
+/// An entity of a model, with its types, relationships and property values.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelEntity {
+    pub id: String,
+    pub types: Vec<Curie>,
+    pub relationships: Vec<ModelRelationship>,
+    pub properties: Vec<ModelPropertyValue>,
+}
+
+/// A relationship from a model entity to another one.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelRelationship {
+    pub relationship: Curie,
+    pub object: String,
+}
+
+/// The value of an entity property, as its `brick:value` and `brick:hasUnit`.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPropertyValue {
+    pub property: Curie,
+    pub value: String,
+    pub datatype: Curie,
+    pub unit: Option<Curie>,
+}
+
+impl<B: Deref<Target = Brick>> BrickModel<B> {
+    /// Create an empty model whose entities are named in the given namespace.
+    /// The ontology prefixes are used to resolve the CURIEs, so neither the prefix
+    /// nor the namespace may already be one of them.
+    pub fn new(brick: B, prefix: &str, namespace: &str) -> Result<Self> {
+        Namespace::new(namespace).map_err(|_| anyhow!("Invalid model namespace {namespace}"))?;
+
+        if brick.prefixes().get_ns(prefix).is_some() {
+            return Err(anyhow!("Prefix {prefix} is already used by the ontology"));
+        }
+        if brick.prefixes().get_prefix(namespace).is_some() {
+            return Err(anyhow!(
+                "Namespace {namespace} is already used by the ontology"
+            ));
+        }
+
+        let mut prefixes = PrefixNamespaceMap::new(&HashMap::from([(
+            prefix.to_string(),
+            namespace.to_string(),
+        )]));
+        prefixes.extend(
+            &brick
+                .prefixes()
+                .iter()
+                .map(|(prefix, ns)| (prefix.to_string(), ns.to_string()))
+                .collect(),
+        );
+
+        Ok(Self {
+            brick,
+            graph: FastGraph::new(),
+            prefixes,
+            prefix: prefix.to_string(),
+            next_value: 0,
+        })
+    }
+
+    /// The prefix of the model namespace.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// The model entities, sorted by id.
+    pub fn entities(&self) -> Result<Vec<String>> {
+        let mut entities = self
+            .graph
+            .triples_matching(Any, [rdf::type_], Any)
+            .map(|triple| Ok(self.entity_id(triple?.s())))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        entities.sort();
+        entities.dedup();
+
+        Ok(entities)
+    }
+
+    /// Describe an entity of the model.
+    pub fn entity(&self, id: &str) -> Result<ModelEntity> {
+        let entity = self.existing_entity(id)?;
+        let brick_value = self.curie_term(&Curie::new("brick", "value"))?;
+        let has_unit = self.curie_term(&Curie::new("brick", "hasUnit"))?;
+
+        let mut desc = ModelEntity {
+            id: id.to_string(),
+            ..Default::default()
+        };
+
+        for triple in self.graph.triples_matching([&entity], Any, Any) {
+            let triple = triple?;
+            let predicate = Curie::from_term(&triple.p().into_term(), &self.prefixes)?;
+            let object = triple.o().into_term::<SimpleTerm>();
+
+            if Term::eq(triple.p(), rdf::type_) {
+                desc.types.push(Curie::from_term(&object, &self.prefixes)?);
+            } else if object.is_blank_node() {
+                let value = self
+                    .graph
+                    .triples_matching([&object], [&brick_value], Any)
+                    .next()
+                    .transpose()?
+                    .map(|tr| tr.o().into_term::<SimpleTerm>());
+
+                let Some(value) = value else {
+                    continue;
+                };
+
+                desc.properties.push(ModelPropertyValue {
+                    property: predicate,
+                    value: value
+                        .lexical_form()
+                        .map_or(String::new(), |v| v.to_string()),
+                    datatype: value
+                        .datatype()
+                        .map(|datatype| Curie::from_iri(datatype, &self.prefixes))
+                        .transpose()?
+                        .unwrap_or_default(),
+                    unit: self
+                        .graph
+                        .triples_matching([&object], [&has_unit], Any)
+                        .next()
+                        .transpose()?
+                        .map(|tr| Curie::from_term(&tr.o().into_term(), &self.prefixes))
+                        .transpose()?,
+                });
+            } else if let Some(object) = self.entity_id(&object) {
+                desc.relationships.push(ModelRelationship {
+                    relationship: predicate,
+                    object,
+                });
+            }
+        }
+
+        desc.types.sort();
+        desc.relationships.sort_by(|a, b| {
+            Ord::cmp(&a.relationship, &b.relationship).then_with(|| Ord::cmp(&a.object, &b.object))
+        });
+        desc.properties
+            .sort_by(|a, b| Ord::cmp(&a.property, &b.property));
+
+        Ok(desc)
+    }
+
+    /// Add an entity of the given class, or one more class to an existing entity.
+    pub fn add_entity(&mut self, id: &str, class: &Curie) -> Result<()> {
+        if !self.brick.is_class(class)? {
+            return Err(anyhow!("Unknown class {class}"));
+        }
+
+        let entity = self.entity_term(id)?;
+        let class = self.curie_term(class)?;
+
+        self.graph.insert(entity, rdf::type_, class)?;
+
+        Ok(())
+    }
+
+    /// Remove an entity, with its properties and all the relationships to or from it.
+    pub fn remove_entity(&mut self, id: &str) -> Result<()> {
+        let entity = self.existing_entity(id)?;
+
+        let mut removed = self.collect_triples([&entity], Any, Any)?;
+        removed.extend(self.collect_triples(Any, Any, [&entity])?);
+
+        for [_, _, o] in removed.clone() {
+            if o.is_blank_node() {
+                removed.extend(self.collect_triples([&o], Any, Any)?);
+            }
+        }
+
+        self.remove_triples(removed)
+    }
+
+    /// Relate two entities of the model, if the ontology allows the relationship
+    /// between any of the subject classes and any of the object classes.
+    pub fn relate(&mut self, subject: &str, relationship: &Curie, object: &str) -> Result<()> {
+        let subject_types = self.entity(subject)?.types;
+        let object_types = self.entity(object)?.types;
+
+        let mut allowed = false;
+        'check: for subject_type in &subject_types {
+            for object_type in &object_types {
+                if self
+                    .brick
+                    .allowed_relationships(subject_type, object_type)?
+                    .iter()
+                    .any(|allowed| &allowed.relationship == relationship)
+                {
+                    allowed = true;
+                    break 'check;
+                }
+            }
+        }
+
+        if !allowed {
+            return Err(anyhow!(
+                "{relationship} is not allowed from {subject} to {object}"
+            ));
+        }
+
+        let triple = [
+            self.entity_term(subject)?,
+            self.curie_term(relationship)?,
+            self.entity_term(object)?,
+        ];
+        self.graph.insert_triple(triple)?;
+
+        Ok(())
+    }
+
+    /// Remove a relationship between two entities.
+    pub fn remove_relationship(
+        &mut self,
+        subject: &str,
+        relationship: &Curie,
+        object: &str,
+    ) -> Result<()> {
+        let triple = [
+            self.entity_term(subject)?,
+            self.curie_term(relationship)?,
+            self.entity_term(object)?,
+        ];
+
+        if !self.graph.remove_triple(triple)? {
+            return Err(anyhow!(
+                "{subject} is not related to {object} by {relationship}"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Set the value of an entity property, replacing the previous one.
+    ///
+    /// The property must be one of the entity classes properties, with a value
+    /// shape or a datatype; relationships are set with `relate`. The value must
+    /// be valid for one of the property datatypes, be one of its enumerated values
+    /// and within its limits. The unit, when given, must be one of the property
+    /// units or applicable to its quantity kind.
+    pub fn set_property(
+        &mut self,
+        entity: &str,
+        property: &Curie,
+        value: &str,
+        unit: Option<&Curie>,
+    ) -> Result<()> {
+        let types = self.entity(entity)?.types;
+
+        let desc = self.brick.entity_property_desc(property)?;
+
+        if desc.value_shape.is_none() && desc.datatypes.is_empty() {
+            return Err(anyhow!(
+                "{property} has no literal value, use relate to link {entity} to another entity"
+            ));
+        }
+
+        if !self.is_property_of(property, &types)? {
+            return Err(anyhow!("{property} is not a property of {entity}"));
+        }
+
+        if !desc.enumeration.is_empty() && !desc.enumeration.iter().any(|v| v == value) {
+            return Err(anyhow!(
+                "{value} is not a value of {property}, expecting one of {}",
+                desc.enumeration.join(", ")
+            ));
+        }
+
+        let datatype = if desc.datatypes.is_empty() {
+            Curie::new("xsd", "string")
+        } else {
+            desc.datatypes
+                .iter()
+                .find(|datatype| is_valid_literal(value, datatype))
+                .cloned()
+                .ok_or_else(|| {
+                    anyhow!(
+                        "{value} is not a valid {} for {property}",
+                        desc.datatypes
+                            .iter()
+                            .map(|datatype| datatype.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" or ")
+                    )
+                })?
+        };
+
+        if let Ok(number) = value.parse::<f64>() {
+            if desc.min_inclusive.is_some_and(|min| number < min)
+                || desc.max_inclusive.is_some_and(|max| number > max)
+                || desc.min_exclusive.is_some_and(|min| number <= min)
+                || desc.max_exclusive.is_some_and(|max| number >= max)
+            {
+                return Err(anyhow!("{value} is out of range for {property}"));
+            }
+        }
+
+        if let Some(unit) = unit {
+            let applicable = desc.units.contains(unit)
+                || match &desc.quantity_kind {
+                    Some(quantity) => self.brick.is_unit_applicable(quantity, unit)?,
+                    None => false,
+                };
+
+            if !applicable {
+                return Err(anyhow!("{unit} is not a unit of {property}"));
+            }
+        }
+
+        if self.has_property(entity, property)? {
+            self.remove_property(entity, property)?;
+        }
+
+        let node = SimpleTerm::BlankNode(BnodeId::new_unchecked(MownStr::from(format!(
+            "v{}",
+            self.next_value
+        ))));
+        self.next_value += 1;
+
+        let literal = SimpleTerm::LiteralDatatype(
+            MownStr::from(value.to_string()),
+            self.curie_term(&datatype)?
+                .iri()
+                .map(|iri| IriRef::new_unchecked(MownStr::from(iri.as_str().to_string())))
+                .ok_or_else(|| anyhow!("Invalid datatype {datatype}"))?,
+        );
+
+        let triple = [
+            self.entity_term(entity)?,
+            self.curie_term(property)?,
+            node.clone(),
+        ];
+        self.graph.insert_triple(triple)?;
+
+        let triple = [
+            node.clone(),
+            self.curie_term(&Curie::new("brick", "value"))?,
+            literal,
+        ];
+        self.graph.insert_triple(triple)?;
+
+        if let Some(unit) = unit {
+            let triple = [
+                node,
+                self.curie_term(&Curie::new("brick", "hasUnit"))?,
+                self.curie_term(unit)?,
+            ];
+            self.graph.insert_triple(triple)?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove the value of an entity property.
+    pub fn remove_property(&mut self, entity: &str, property: &Curie) -> Result<()> {
+        let subject = self.existing_entity(entity)?;
+        let predicate = self.curie_term(property)?;
+
+        let mut removed = self.collect_triples([&subject], [&predicate], Any)?;
+
+        if removed.is_empty() {
+            return Err(anyhow!("{entity} has no {property}"));
+        }
+
+        for [_, _, o] in removed.clone() {
+            if o.is_blank_node() {
+                removed.extend(self.collect_triples([&o], Any, Any)?);
+            }
+        }
+
+        self.remove_triples(removed)
+    }
+
+    /// Write the model in the given format, with the namespaces as CURIEs.
+    /// The output is sorted and the property value nodes are labelled after their
+    /// entity and property, so a model changes only where its triples do.
+    pub fn export(&self, format: ModelFormat) -> Result<String> {
+        export::serialize(
+            &self.graph,
+            &self.prefixes,
+            &self.brick.object_properties()?,
+            format,
+        )
+    }
+
+    /// Check that one of the classes, or their superclasses, has a property shape
+    /// with the property or one of its super properties as path.
+    fn is_property_of(&self, property: &Curie, classes: &[Curie]) -> Result<bool> {
+        let mut properties = vec![property.clone()];
+        let mut i = 0;
+
+        while let Some(cur) = properties.get(i) {
+            for super_property in self.brick.relationship_desc(cur)?.super_properties {
+                if !properties.contains(&super_property) {
+                    properties.push(super_property);
+                }
+            }
+            i += 1;
+        }
+
+        for class in classes {
+            if self
+                .brick
+                .property_paths(class)?
+                .iter()
+                .any(|path| properties.contains(path))
+            {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn has_property(&self, entity: &str, property: &Curie) -> Result<bool> {
+        Ok(self
+            .graph
+            .triples_matching(
+                [self.entity_term(entity)?],
+                [self.curie_term(property)?],
+                Any,
+            )
+            .next()
+            .is_some())
+    }
+
+    /// The term of an entity that must have a type in the model.
+    fn existing_entity(&self, id: &str) -> Result<SimpleTerm<'static>> {
+        let entity = self.entity_term(id)?;
+
+        if self
+            .graph
+            .triples_matching([&entity], [rdf::type_], Any)
+            .next()
+            .is_none()
+        {
+            return Err(anyhow!("Unknown entity {id}"));
+        }
+
+        Ok(entity)
+    }
+
+    fn entity_term(&self, id: &str) -> Result<SimpleTerm<'static>> {
+        self.curie_term(&Curie::new(&self.prefix, id))
+    }
+
+    fn curie_term(&self, curie: &Curie) -> Result<SimpleTerm<'static>> {
+        let ns = self
+            .prefixes
+            .get_ns(&curie.prefix)
+            .ok_or_else(|| anyhow!("Missing prefix {}", curie.prefix))?;
+
+        Ok(ns.get(&curie.local_name)?.into_term())
+    }
+
+    /// The id of a term naming an entity in the model namespace.
+    fn entity_id<T: Term>(&self, term: T) -> Option<String> {
+        Curie::from_term(&term.into_term(), &self.prefixes)
+            .ok()
+            .filter(|curie| curie.prefix == self.prefix)
+            .map(|curie| curie.local_name)
+    }
+
+    fn collect_triples<S, P, O>(&self, s: S, p: P, o: O) -> Result<Vec<[SimpleTerm<'static>; 3]>>
+    where
+        S: sophia_api::term::matcher::TermMatcher,
+        P: sophia_api::term::matcher::TermMatcher,
+        O: sophia_api::term::matcher::TermMatcher,
+    {
+        self.graph
+            .triples_matching(s, p, o)
+            .map(|triple| Ok(triple?.spo().map(|term| term.into_term())))
+            .collect()
+    }
+
+    fn remove_triples(&mut self, triples: Vec<[SimpleTerm<'static>; 3]>) -> Result<()> {
+        for triple in triples {
+            self.graph.remove_triple(triple)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Check a lexical form against the XSD datatypes that constrain it, others accept any value.
+fn is_valid_literal(value: &str, datatype: &Curie) -> bool {
+    if datatype.prefix != "xsd" {
+        return true;
+    }
+
+    match datatype.local_name.as_str() {
+        "double" | "float" | "decimal" => value.parse::<f64>().is_ok(),
+        "integer" | "int" | "long" | "short" | "byte" => value.parse::<i64>().is_ok(),
+        "nonNegativeInteger" | "unsignedInt" | "unsignedLong" | "unsignedShort"
+        | "unsignedByte" => value.parse::<u64>().is_ok(),
+        "positiveInteger" => value.parse::<u64>().is_ok_and(|v| v > 0),
+        "boolean" => matches!(value, "true" | "false" | "1" | "0"),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brick::{test::test_brick, Brick};
+
+    #[test]
+    fn validates_literals() {
+        let xsd = |name| Curie::new("xsd", name);
+
+        assert!(is_valid_literal("12.5", &xsd("double")));
+        assert!(!is_valid_literal("warm", &xsd("double")));
+        assert!(is_valid_literal("1990", &xsd("nonNegativeInteger")));
+        assert!(!is_valid_literal("-3", &xsd("nonNegativeInteger")));
+        assert!(!is_valid_literal("0", &xsd("positiveInteger")));
+        assert!(is_valid_literal("true", &xsd("boolean")));
+        assert!(!is_valid_literal("yes", &xsd("boolean")));
+        assert!(is_valid_literal("anything", &xsd("string")));
+    }
+
+    #[test]
+    fn rejects_ontology_namespaces() {
+        let brick = test_brick();
+
+        assert!(BrickModel::new(brick, "brick", "urn:example#").is_err());
+        assert!(BrickModel::new(brick, "bldg", "https://brickschema.org/schema/Brick#").is_err());
+    }
+
+    #[test]
+    fn matches_property_namespaces() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix ex: <urn:other#> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+            @prefix skos: <http://www.w3.org/2004/02/skos/core#> .
+
+            brick:Floor a owl:Class, sh:NodeShape ;
+                sh:property [ sh:path ex:area ] .
+            brick:area a owl:ObjectProperty ; skos:definition "The area of the floor" .
+            ex:area a owl:ObjectProperty .
+            "#,
+        )
+        .unwrap();
+        let model = BrickModel::new(&brick, "bldg", "urn:example#").unwrap();
+
+        let floor = [Curie::new("brick", "Floor")];
+        assert!(model
+            .is_property_of(&Curie::new("ex", "area"), &floor)
+            .unwrap());
+        assert!(!model
+            .is_property_of(&Curie::new("brick", "area"), &floor)
+            .unwrap());
+    }
+
+    #[test]
+    fn builds_models() {
+        let brick = test_brick();
+        let mut model = BrickModel::new(brick, "bldg", "urn:example#").unwrap();
+
+        let class = |name| Curie::new("brick", name);
+
+        model.add_entity("AHU1", &class("AHU")).unwrap();
+        model.add_entity("VAV1", &class("VAV")).unwrap();
+        model
+            .add_entity("SAT1", &class("Supply_Air_Temperature_Sensor"))
+            .unwrap();
+        model.add_entity("Floor1", &class("Floor")).unwrap();
+        assert!(model.add_entity("X", &class("Not_A_Class")).is_err());
+        assert_eq!(
+            model.entities().unwrap(),
+            ["AHU1", "Floor1", "SAT1", "VAV1"]
+        );
+
+        model.relate("AHU1", &class("feeds"), "VAV1").unwrap();
+        model.relate("SAT1", &class("isPointOf"), "AHU1").unwrap();
+        assert!(model.relate("VAV1", &class("hasPoint"), "AHU1").is_err());
+        assert!(model.relate("AHU1", &class("feeds"), "Nowhere").is_err());
+
+        model
+            .set_property(
+                "Floor1",
+                &class("area"),
+                "250.5",
+                Some(&Curie::new("unit", "M2")),
+            )
+            .unwrap();
+        assert!(model
+            .set_property("Floor1", &class("area"), "large", None)
+            .is_err());
+        assert!(model
+            .set_property(
+                "Floor1",
+                &class("area"),
+                "10",
+                Some(&Curie::new("unit", "DEG_C"))
+            )
+            .is_err());
+        assert!(model
+            .set_property("AHU1", &class("electricalPhases"), "XYZ", None)
+            .is_err());
+        assert!(model
+            .set_property("AHU1", &class("azimuth"), "400", None)
+            .is_err());
+        let err = model
+            .set_property("SAT1", &class("area"), "10", None)
+            .unwrap_err();
+        assert!(err.to_string().contains("not a property of SAT1"));
+        let err = model
+            .set_property("SAT1", &class("resolution"), "0", None)
+            .unwrap_err();
+        assert!(err.to_string().contains("out of range"));
+        model
+            .set_property("SAT1", &class("resolution"), "0.1", None)
+            .unwrap();
+        model
+            .set_property("AHU1", &class("electricalPhases"), "ABC", None)
+            .unwrap();
+        model
+            .set_property("AHU1", &class("electricalPhases"), "AB", None)
+            .unwrap();
+        let err = model
+            .set_property("AHU1", &class("hasPoint"), "SAT1", None)
+            .unwrap_err();
+        assert!(err.to_string().contains("use relate"));
+
+        let ahu = model.entity("AHU1").unwrap();
+        assert_eq!(ahu.types, [class("AHU")]);
+        assert_eq!(ahu.relationships.len(), 1);
+        assert_eq!(ahu.relationships[0].relationship, class("feeds"));
+        assert_eq!(ahu.relationships[0].object, "VAV1");
+        assert_eq!(ahu.properties.len(), 1);
+        assert_eq!(ahu.properties[0].value, "AB");
+
+        let floor = model.entity("Floor1").unwrap();
+        assert_eq!(floor.properties[0].value, "250.5");
+        assert_eq!(floor.properties[0].unit, Some(Curie::new("unit", "M2")));
+
+        model
+            .remove_relationship("AHU1", &class("feeds"), "VAV1")
+            .unwrap();
+        assert!(model
+            .remove_relationship("AHU1", &class("feeds"), "VAV1")
+            .is_err());
+        model
+            .remove_property("AHU1", &class("electricalPhases"))
+            .unwrap();
+        assert!(model.entity("AHU1").unwrap().properties.is_empty());
+
+        model.remove_entity("AHU1").unwrap();
+        assert!(model.entity("AHU1").is_err());
+        assert!(model.entity("SAT1").unwrap().relationships.is_empty());
+
+        model.remove_entity("Floor1").unwrap();
+        assert_eq!(model.entities().unwrap(), ["SAT1", "VAV1"]);
+    }
+}
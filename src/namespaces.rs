@@ -25,6 +25,19 @@ impl PrefixNamespaceMap {
         }
     }
 
+    /// Add the prefixes not already mapped, existing mappings are kept.
+    pub fn extend(&mut self, prefix_map: &HashMap<String, String>) {
+        for (prefix, ns) in prefix_map {
+            if self.prefix_to_ns.contains_key(prefix) || self.ns_to_prefix.contains_key(ns) {
+                continue;
+            }
+
+            self.prefix_to_ns
+                .insert(prefix.clone(), Namespace::new_unchecked(ns.clone()));
+            self.ns_to_prefix.insert(ns.clone(), prefix.clone());
+        }
+    }
+
     pub fn get_ns(&self, prefix: &str) -> Option<&Namespace<String>> {
         self.prefix_to_ns.get(prefix)
     }
@@ -32,4 +45,15 @@ impl PrefixNamespaceMap {
     pub fn get_prefix(&self, ns: &str) -> Option<&String> {
         self.ns_to_prefix.get(ns)
     }
+
+    /// The prefixes and their namespaces, sorted by prefix.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        let mut entries = self
+            .prefix_to_ns
+            .iter()
+            .map(|(prefix, ns)| (prefix.as_str(), ns.as_str()))
+            .collect::<Vec<_>>();
+        entries.sort();
+        entries.into_iter()
+    }
 }
@@ -6,16 +6,16 @@ use std::fmt::Debug;
 use crate::curie::Curie;
 
 #[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum PropertyPairConstraint {
-    Equal(String),
-    Disjoint(String),
-    LessThan(String),
-    LessThanOrEqual(String),
+    Equal(Curie),
+    Disjoint(Curie),
+    LessThan(Curie),
+    LessThanOrEqual(Curie),
 }
 
 #[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LogicalConstraint {
     Not(Vec<BrickProperty>),
     And(Vec<BrickProperty>),
@@ -24,7 +24,30 @@ pub enum LogicalConstraint {
 }
 
 #[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QualifiedConstraint {
+    /// The referenced shape, `None` for blank node shapes.
+    pub value_shape: Option<Curie>,
+    /// The shape constraints, left empty when the shape is already being collected.
+    pub shape: Box<BrickProperty>,
+    pub min_count: Option<u32>,
+    pub max_count: Option<u32>,
+    pub disjoint: bool,
+}
+
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeShapeRef {
+    /// The referenced shape, `None` for blank node shapes.
+    pub shape: Option<Curie>,
+    /// The shape properties, only filled when expansion is requested.
+    pub properties: Vec<BrickProperty>,
+}
+
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BrickProperty {
     pub path: String,
@@ -49,6 +72,44 @@ pub struct BrickProperty {
 
     pub constraints: Vec<PropertyPairConstraint>,
     pub logical_constraints: Vec<LogicalConstraint>,
+    pub qualified: Option<QualifiedConstraint>,
+    pub node: Option<NodeShapeRef>,
     pub one_of: Vec<String>,
     pub has_value_of: String,
 }
+
+/// A property shape of a class, possibly inherited from one of its superclasses.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InheritedProperty {
+    pub property: BrickProperty,
+    /// The closest class declaring the property.
+    pub defined_in: Curie,
+    /// Ancestors declaring the same property, their constraints are merged in.
+    pub overrides: Vec<Curie>,
+}
+
+/// The expected value of an entity property, such as `brick:area` or `brick:yearBuilt`,
+/// whose values are nodes carrying `brick:value` and `brick:hasUnit`.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityPropertyDesc {
+    pub property: Curie,
+    pub label: String,
+    pub definition: String,
+    /// The node shape of the property values, such as `bsh:AreaShape`.
+    pub value_shape: Option<Curie>,
+    /// The accepted datatypes of `brick:value`.
+    pub datatypes: Vec<Curie>,
+    /// The accepted units of `brick:hasUnit`.
+    pub units: Vec<Curie>,
+    pub quantity_kind: Option<Curie>,
+    /// The allowed values of `brick:value`, when it is an enumeration.
+    pub enumeration: Vec<String>,
+    pub min_inclusive: Option<f64>,
+    pub max_inclusive: Option<f64>,
+    pub min_exclusive: Option<f64>,
+    pub max_exclusive: Option<f64>,
+}
@@ -0,0 +1,314 @@
+// Copyright (C) 2025 Radu Racariu.
+
+//!
+//! QUDT quantity kinds, units and dimension vectors.
+//!
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::curie::Curie;
+
+/// A QUDT quantity kind, such as `quantitykind:Temperature`, or a Brick quantity.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuantityKind {
+    pub curie: Curie,
+    pub label: String,
+    pub definition: String,
+    /// The dimension vector, inherited from the broader quantity kinds when not declared.
+    pub dimension_vector: Option<DimensionVector>,
+    pub applicable_units: Vec<Curie>,
+    pub broader: Vec<Curie>,
+}
+
+/// A QUDT unit, such as `unit:DEG_C`.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Unit {
+    pub curie: Curie,
+    pub label: String,
+    pub symbol: String,
+    pub dimension_vector: Option<DimensionVector>,
+    /// Multiplier converting a value to the SI base unit.
+    pub conversion_multiplier: Option<f64>,
+    /// Offset added before the multiplier when converting to the SI base unit.
+    pub conversion_offset: Option<f64>,
+    pub quantity_kinds: Vec<Curie>,
+}
+
+impl Unit {
+    /// Convert a value in this unit to the SI base unit of its dimension.
+    pub fn to_base(&self, value: f64) -> Result<f64> {
+        Ok((value + self.conversion_offset.unwrap_or_default()) * self.multiplier()?)
+    }
+
+    /// Convert a value in the SI base unit of its dimension to this unit.
+    pub fn from_base(&self, value: f64) -> Result<f64> {
+        Ok(value / self.multiplier()? - self.conversion_offset.unwrap_or_default())
+    }
+
+    fn multiplier(&self) -> Result<f64> {
+        match self.conversion_multiplier {
+            Some(multiplier) if multiplier != 0.0 => Ok(multiplier),
+            _ => Err(anyhow!("Unit {} has no conversion multiplier", self.curie)),
+        }
+    }
+}
+
+/// Convert a value between two units, refusing units of different dimensions.
+pub fn convert(value: f64, from: &Unit, to: &Unit) -> Result<f64> {
+    match (&from.dimension_vector, &to.dimension_vector) {
+        (Some(a), Some(b)) if a == b => {}
+        (Some(_), Some(_)) => {
+            return Err(anyhow!(
+                "Can't convert {} to {}, the dimensions differ",
+                from.curie,
+                to.curie
+            ))
+        }
+        _ => {
+            return Err(anyhow!(
+                "Can't convert {} to {}, missing dimension vector",
+                from.curie,
+                to.curie
+            ))
+        }
+    }
+
+    to.from_base(from.to_base(value)?)
+}
+
+/// The exponents of the base dimensions of a quantity, as encoded by the
+/// QUDT dimension vector names, such as `A0E0L1I0M0H0T-1D0` for a speed.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DimensionVector {
+    pub amount_of_substance: f64,
+    pub electric_current: f64,
+    pub length: f64,
+    pub luminous_intensity: f64,
+    pub mass: f64,
+    pub temperature: f64,
+    pub time: f64,
+    pub dimensionless: f64,
+}
+
+impl DimensionVector {
+    /// Check if all the base dimension exponents are zero.
+    pub fn is_dimensionless(&self) -> bool {
+        [
+            self.amount_of_substance,
+            self.electric_current,
+            self.length,
+            self.luminous_intensity,
+            self.mass,
+            self.temperature,
+            self.time,
+        ]
+        .iter()
+        .all(|exp| *exp == 0.0)
+    }
+}
+
+impl FromStr for DimensionVector {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        let mut vector = DimensionVector::default();
+        let mut rest = name;
+
+        for (symbol, exponent) in [
+            ('A', &mut vector.amount_of_substance),
+            ('E', &mut vector.electric_current),
+            ('L', &mut vector.length),
+            ('I', &mut vector.luminous_intensity),
+            ('M', &mut vector.mass),
+            ('H', &mut vector.temperature),
+            ('T', &mut vector.time),
+            ('D', &mut vector.dimensionless),
+        ] {
+            rest = rest
+                .strip_prefix(symbol)
+                .ok_or_else(|| anyhow!("Invalid dimension vector {name}"))?;
+
+            let end = rest
+                .find(|c: char| c.is_ascii_uppercase())
+                .unwrap_or(rest.len());
+
+            *exponent = rest[..end]
+                .replace("dot", ".")
+                .replace("pt", ".")
+                .parse()
+                .map_err(|_| anyhow!("Invalid dimension vector {name}"))?;
+
+            rest = &rest[end..];
+        }
+
+        if !rest.is_empty() {
+            return Err(anyhow!("Invalid dimension vector {name}"));
+        }
+
+        Ok(vector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brick::test::parse_test_brick;
+
+    fn unit(name: &str, dimension: &str, multiplier: f64, offset: Option<f64>) -> Unit {
+        Unit {
+            curie: Curie::new("unit", name),
+            dimension_vector: dimension.parse().ok(),
+            conversion_multiplier: Some(multiplier),
+            conversion_offset: offset,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn converts_units() {
+        let deg_c = unit("DEG_C", "A0E0L0I0M0H1T0D0", 1.0, Some(273.15));
+        let deg_f = unit(
+            "DEG_F",
+            "A0E0L0I0M0H1T0D0",
+            0.5555555555555556,
+            Some(459.669607),
+        );
+        let cfm = unit("FT3-PER-MIN", "A0E0L3I0M0H0T-1D0", 0.000471947443, None);
+        let lps = unit("L-PER-SEC", "A0E0L3I0M0H0T-1D0", 0.001, None);
+
+        assert!((convert(212.0, &deg_f, &deg_c).unwrap() - 100.0).abs() < 1e-3);
+        assert!((convert(-40.0, &deg_c, &deg_f).unwrap() + 40.0).abs() < 1e-3);
+        assert!((convert(0.0, &deg_c, &deg_f).unwrap() - 32.0).abs() < 1e-3);
+        assert!((convert(1.0, &cfm, &lps).unwrap() - 0.471947443).abs() < 1e-9);
+
+        assert!(convert(1.0, &deg_c, &lps).is_err());
+        assert!(convert(1.0, &deg_c, &Unit::default()).is_err());
+    }
+
+    #[test]
+    fn parses_dimension_vectors() {
+        let pressure: DimensionVector = "A0E0L-1I0M1H0T-2D0".parse().unwrap();
+        assert_eq!(pressure.length, -1.0);
+        assert_eq!(pressure.mass, 1.0);
+        assert_eq!(pressure.time, -2.0);
+        assert!(!pressure.is_dimensionless());
+
+        let ratio: DimensionVector = "A0E0L0I0M0H0T0D1".parse().unwrap();
+        assert_eq!(ratio.dimensionless, 1.0);
+        assert!(ratio.is_dimensionless());
+
+        let root: DimensionVector = "A0E0L0dot5I0M0H0T0D0".parse().unwrap();
+        assert_eq!(root.length, 0.5);
+
+        assert_ne!(pressure, ratio);
+        assert!("A0E0L1".parse::<DimensionVector>().is_err());
+        assert!("Temperature".parse::<DimensionVector>().is_err());
+    }
+
+    const QUDT_SAMPLE: &str = r#"
+        @prefix qkdv: <http://qudt.org/vocab/dimensionvector/> .
+        @prefix qudt: <http://qudt.org/schema/qudt/> .
+        @prefix quantitykind: <http://qudt.org/vocab/quantitykind/> .
+        @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+        @prefix unit: <http://qudt.org/vocab/unit/> .
+
+        quantitykind:Temperature rdfs:label "Temperatur"@de, "Temperature"@en ;
+            qudt:hasDimensionVector qkdv:A0E0L0I0M0H1T0D0 ;
+            qudt:applicableUnit unit:DEG_C .
+
+        unit:DEG_C a qudt:Unit ;
+            rdfs:label "Degree Celsius"@en ;
+            qudt:symbol "°C" ;
+            qudt:hasDimensionVector qkdv:A0E0L0I0M0H1T0D0 ;
+            qudt:conversionMultiplier 1.0 ;
+            qudt:conversionOffset 273.15 ;
+            qudt:hasQuantityKind quantitykind:Temperature .
+
+        unit:DEG_F a qudt:Unit ;
+            rdfs:label "Degree Fahrenheit"@en ;
+            qudt:symbol "°F" ;
+            qudt:hasDimensionVector qkdv:A0E0L0I0M0H1T0D0 ;
+            qudt:conversionMultiplier 0.5555555555555556 ;
+            qudt:conversionOffset 459.669607 ;
+            qudt:hasQuantityKind quantitykind:Temperature .
+
+        unit:K a qudt:Unit ;
+            rdfs:label "Kelvin"@en ;
+            qudt:symbol "K" ;
+            qudt:hasDimensionVector qkdv:A0E0L0I0M0H1T0D0 ;
+            qudt:conversionMultiplier 1.0 ;
+            qudt:conversionOffset 0.0 .
+
+        unit:PA a qudt:Unit ;
+            rdfs:label "Pascal"@en ;
+            qudt:symbol "Pa" ;
+            qudt:hasDimensionVector qkdv:A0E0L-1I0M1H0T-2D0 ;
+            qudt:conversionMultiplier 1.0 ;
+            qudt:hasQuantityKind quantitykind:Pressure .
+    "#;
+
+    #[test]
+    fn reads_qudt_vocabulary() {
+        let mut brick = parse_test_brick();
+        brick.load(QUDT_SAMPLE).unwrap();
+
+        let sensor = Curie::new("brick", "Air_Temperature_Sensor");
+        let quantity = brick.quantity_of(&sensor).unwrap().unwrap();
+        assert_eq!(quantity.curie, Curie::new("quantitykind", "Temperature"));
+        assert_eq!(quantity.label, "Temperature");
+        assert_eq!(quantity.dimension_vector.unwrap().temperature, 1.0);
+
+        let units = brick.applicable_units(&sensor).unwrap();
+        assert_eq!(
+            units,
+            [Curie::new("unit", "DEG_C"), Curie::new("unit", "DEG_F")]
+        );
+
+        let deg_f = brick.unit(&Curie::new("unit", "DEG_F")).unwrap();
+        assert_eq!(deg_f.symbol, "°F");
+        assert_eq!(deg_f.conversion_offset, Some(459.669607));
+        assert_eq!(
+            deg_f.quantity_kinds,
+            [Curie::new("quantitykind", "Temperature")]
+        );
+
+        assert!(brick
+            .is_unit_applicable(&sensor, &Curie::new("unit", "DEG_F"))
+            .unwrap());
+        assert!(brick
+            .is_unit_applicable(&sensor, &Curie::new("unit", "K"))
+            .unwrap());
+        assert!(!brick
+            .is_unit_applicable(&sensor, &Curie::new("unit", "PA"))
+            .unwrap());
+
+        let converted = brick
+            .convert(
+                212.0,
+                &Curie::new("unit", "DEG_F"),
+                &Curie::new("unit", "DEG_C"),
+            )
+            .unwrap();
+        assert!((converted - 100.0).abs() < 1e-3);
+        assert!(brick
+            .convert(1.0, &Curie::new("unit", "K"), &Curie::new("unit", "PA"))
+            .is_err());
+
+        let level = brick.quantity_kind(&Curie::new("brick", "Level")).unwrap();
+        assert!(level.applicable_units.contains(&Curie::new("unit", "M")));
+        assert_eq!(level.dimension_vector.unwrap().length, 1.0);
+
+        assert!(brick
+            .quantity_of(&Curie::new("brick", "Equipment"))
+            .unwrap()
+            .is_none());
+    }
+}
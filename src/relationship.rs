@@ -0,0 +1,286 @@
+// Copyright (C) 2025 Radu Racariu.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sophia::inmem::graph::FastGraph;
+use sophia_api::{
+    graph::Graph,
+    ns::{owl, rdf, rdfs},
+    term::matcher::Any,
+    triple::Triple,
+};
+
+use crate::{curie::Curie, hierarchy::ClassHierarchy, namespaces::PrefixNamespaceMap};
+
+/// A relationship between entities, such as `brick:hasPoint` or `brick:feeds`.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrickRelationship {
+    pub name: String,
+    pub namespace: String,
+    pub label: String,
+    pub definition: String,
+    /// The relationship declared with `owl:inverseOf`, in either direction.
+    pub inverse: Option<Curie>,
+    pub domain: Vec<Curie>,
+    pub range: Vec<Curie>,
+    pub super_properties: Vec<Curie>,
+    pub sub_properties: Vec<Curie>,
+    pub equivalent_properties: Vec<Curie>,
+    pub characteristics: Vec<PropertyCharacteristic>,
+}
+
+/// The OWL characteristics of a relationship.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PropertyCharacteristic {
+    Asymmetric,
+    Irreflexive,
+    Reflexive,
+    Symmetric,
+    Transitive,
+    Functional,
+    InverseFunctional,
+}
+
+impl PropertyCharacteristic {
+    /// The characteristic for an OWL property type name, such as `AsymmetricProperty`.
+    pub fn from_owl_type(name: &str) -> Option<Self> {
+        match name {
+            "AsymmetricProperty" => Some(Self::Asymmetric),
+            "IrreflexiveProperty" => Some(Self::Irreflexive),
+            "ReflexiveProperty" => Some(Self::Reflexive),
+            "SymmetricProperty" => Some(Self::Symmetric),
+            "TransitiveProperty" => Some(Self::Transitive),
+            "FunctionalProperty" => Some(Self::Functional),
+            "InverseFunctionalProperty" => Some(Self::InverseFunctional),
+            _ => None,
+        }
+    }
+}
+
+/// A relationship allowed from a subject class to an object class.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowedRelationship {
+    pub relationship: Curie,
+    /// The node shape, usually a class, declaring the permitting property shape.
+    /// `None` when the relationship is only allowed by its domain and range.
+    pub shape: Option<Curie>,
+    /// The permitting property shape, when it is a named one.
+    pub property_shape: Option<Curie>,
+    /// The permitting shape constrains the inverse relationship, from the object to the subject.
+    pub from_inverse: bool,
+    pub message: String,
+}
+
+/// Precomputed `rdfs:domain` and `rdfs:range` of the object properties declaring both.
+#[derive(Default, Debug)]
+pub(crate) struct DomainRangeIndex {
+    relationships: Vec<(Curie, Vec<Curie>, Vec<Curie>)>,
+}
+
+impl DomainRangeIndex {
+    pub(crate) fn new(graph: &FastGraph, prefixes: &PrefixNamespaceMap) -> Result<Self> {
+        let mut index = DomainRangeIndex::default();
+
+        for triple in graph.triples_matching(Any, [rdf::type_], [owl::ObjectProperty]) {
+            let property = triple?.s().clone();
+            let Ok(relationship) = Curie::from_term(&property, prefixes) else {
+                continue;
+            };
+
+            let linked = |predicate| -> Result<Vec<Curie>> {
+                let mut curies = Vec::new();
+                for triple in graph.triples_matching([&property], [predicate], Any) {
+                    if let Ok(curie) = Curie::from_term(triple?.o(), prefixes) {
+                        curies.push(curie);
+                    }
+                }
+                curies.sort();
+                curies.dedup();
+                Ok(curies)
+            };
+
+            let (domain, range) = (linked(rdfs::domain)?, linked(rdfs::range)?);
+            if !domain.is_empty() && !range.is_empty() {
+                index.relationships.push((relationship, domain, range));
+            }
+        }
+
+        index.relationships.sort();
+        index.relationships.dedup();
+
+        Ok(index)
+    }
+
+    /// The relationships whose domain includes the subject and range the object.
+    pub(crate) fn allowed<'a>(
+        &'a self,
+        hierarchy: &'a ClassHierarchy,
+        subject: &'a Curie,
+        object: &'a Curie,
+    ) -> impl Iterator<Item = &'a Curie> {
+        self.relationships
+            .iter()
+            .filter(|(_, domain, range)| {
+                domain
+                    .iter()
+                    .any(|class| hierarchy.is_subclass_of(subject, class))
+                    && range
+                        .iter()
+                        .any(|class| hierarchy.is_subclass_of(object, class))
+            })
+            .map(|(relationship, _, _)| relationship)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brick::{test::test_brick, Brick};
+
+    #[test]
+    fn describes_relationships() {
+        let brick = test_brick();
+
+        let has_point = brick
+            .relationship_desc(&Curie::new("brick", "hasPoint"))
+            .unwrap();
+        assert_eq!(has_point.name, "hasPoint");
+        assert_eq!(has_point.label, "Has point");
+        assert!(has_point.definition.starts_with("The subject has a source"));
+        assert_eq!(has_point.inverse, Some(Curie::new("brick", "isPointOf")));
+        assert_eq!(
+            has_point.characteristics,
+            [
+                PropertyCharacteristic::Asymmetric,
+                PropertyCharacteristic::Irreflexive
+            ]
+        );
+
+        let is_point_of = brick
+            .relationship_desc(&Curie::new("brick", "isPointOf"))
+            .unwrap();
+        assert_eq!(is_point_of.inverse, Some(Curie::new("brick", "hasPoint")));
+
+        assert!(brick
+            .relationship_desc(&Curie::new("brick", "notARelationship"))
+            .is_err());
+    }
+
+    #[test]
+    fn skips_unknown_inverses() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+
+            brick:Meter a owl:Class, sh:NodeShape ;
+                sh:property [ sh:path brick:undeclared ; sh:class brick:Building ] .
+            brick:Building a owl:Class .
+            "#,
+        )
+        .unwrap();
+
+        let allowed = brick
+            .allowed_relationships(
+                &Curie::new("brick", "Building"),
+                &Curie::new("brick", "Meter"),
+            )
+            .unwrap();
+        assert!(allowed.is_empty());
+
+        let allowed = brick
+            .allowed_relationships(
+                &Curie::new("brick", "Meter"),
+                &Curie::new("brick", "Building"),
+            )
+            .unwrap();
+        assert_eq!(allowed[0].relationship, Curie::new("brick", "undeclared"));
+    }
+
+    #[test]
+    fn excludes_negated_classes() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+
+            brick:Meter a owl:Class, sh:NodeShape ;
+                sh:property [ sh:path brick:meters ; sh:class brick:Equipment ;
+                    sh:not [ sh:class brick:AHU ] ] .
+            brick:Equipment a owl:Class .
+            brick:AHU a owl:Class ; rdfs:subClassOf brick:Equipment .
+            brick:VAV a owl:Class ; rdfs:subClassOf brick:Equipment .
+            "#,
+        )
+        .unwrap();
+
+        let allowed = |object| {
+            brick
+                .allowed_relationships(&Curie::new("brick", "Meter"), &Curie::new("brick", object))
+                .unwrap()
+        };
+        assert_eq!(
+            allowed("VAV")[0].relationship,
+            Curie::new("brick", "meters")
+        );
+        assert!(allowed("AHU").is_empty());
+    }
+
+    #[test]
+    fn allows_relationships() {
+        let brick = test_brick();
+
+        let allowed = brick
+            .allowed_relationships(&Curie::new("brick", "AHU"), &Curie::new("brick", "VAV"))
+            .unwrap();
+
+        let feeds = allowed
+            .iter()
+            .find(|a| a.relationship == Curie::new("brick", "feeds"))
+            .unwrap();
+        assert!(brick.is_subclass_of(
+            &Curie::new("brick", "Air_Handling_Unit"),
+            feeds.shape.as_ref().unwrap()
+        ));
+        assert!(!feeds.from_inverse);
+        assert!(allowed
+            .iter()
+            .any(|a| a.relationship == Curie::new("brick", "hasPart")));
+        assert!(!allowed
+            .iter()
+            .any(|a| a.relationship == Curie::new("brick", "hasPoint")));
+
+        let allowed = brick
+            .allowed_relationships(
+                &Curie::new("brick", "Supply_Air_Temperature_Sensor"),
+                &Curie::new("brick", "Air_Handling_Unit"),
+            )
+            .unwrap();
+        let is_point_of = allowed
+            .iter()
+            .find(|a| a.relationship == Curie::new("brick", "isPointOf"))
+            .unwrap();
+        assert!(!is_point_of.from_inverse);
+        assert!(!allowed
+            .iter()
+            .any(|a| a.relationship == Curie::new("brick", "feeds")));
+
+        let allowed = brick
+            .allowed_relationships(&Curie::new("brick", "VAV"), &Curie::new("brick", "AHU"))
+            .unwrap();
+        let is_fed_by = allowed
+            .iter()
+            .find(|a| a.relationship == Curie::new("brick", "isFedBy"))
+            .unwrap();
+        assert!(is_fed_by.from_inverse);
+    }
+}
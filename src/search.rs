@@ -0,0 +1,378 @@
+// Copyright (C) 2025 Radu Racariu.
+
+//!
+//! In-process full-text search over the ontology classes.
+//!
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sophia::inmem::graph::FastGraph;
+use sophia_api::{
+    graph::Graph,
+    ns::{owl, rdf, rdfs},
+    term::{matcher::Any, Term},
+    triple::Triple,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+
+use crate::{curie::Curie, namespaces::PrefixNamespaceMap};
+
+/// A class matching a search query.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub class: Curie,
+    pub label: String,
+    pub score: f64,
+}
+
+/// Where a token was found, ordered by relevance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Field {
+    Name,
+    Label,
+    Tag,
+    Alias,
+    Definition,
+}
+
+impl Field {
+    fn weight(self) -> f64 {
+        match self {
+            Field::Name | Field::Label => 3.0,
+            Field::Tag | Field::Alias => 2.0,
+            Field::Definition => 1.0,
+        }
+    }
+}
+
+/// Inverted index of the tokens found in the class names, labels, definitions,
+/// tags and aliases.
+#[derive(Default, Debug)]
+pub(crate) struct SearchIndex {
+    classes: Vec<(Curie, String)>,
+    /// Sorted, so that the tokens starting with a prefix are a range.
+    tokens: BTreeMap<String, Vec<(usize, Field)>>,
+    /// The tokens by their length in characters, to look for typos only
+    /// among the tokens of a close length.
+    lengths: HashMap<usize, Vec<String>>,
+}
+
+impl SearchIndex {
+    /// Index the classes, the ones that are not their own canonical class, such as
+    /// aliases, are indexed with their canonical class.
+    pub(crate) fn new(
+        graph: &FastGraph,
+        prefixes: &PrefixNamespaceMap,
+        canonical_class: impl Fn(&Curie) -> Result<Curie>,
+    ) -> Result<Self> {
+        let mut index = SearchIndex::default();
+
+        let brick = prefixes.get_ns("brick");
+        let skos = prefixes.get_ns("skos");
+
+        let mut classes = Vec::new();
+        let mut aliases = HashMap::<Curie, Vec<Curie>>::new();
+        for triple in graph.triples_matching(Any, [&rdf::type_], [&owl::Class]) {
+            let triple = triple?;
+            let Ok(class) = Curie::from_term(triple.s(), prefixes) else {
+                continue;
+            };
+
+            let canonical = canonical_class(&class)?;
+            if canonical == class {
+                classes.push((triple.s().clone(), class));
+            } else {
+                aliases.entry(canonical).or_default().push(class);
+            }
+        }
+
+        for (term, class) in classes {
+            let id = index.classes.len();
+
+            index.insert(id, Field::Name, &class.local_name);
+            for alias in aliases.get(&class).into_iter().flatten() {
+                index.insert(id, Field::Alias, &alias.local_name);
+            }
+
+            // Every label is searchable, the English or untagged one is displayed.
+            let mut label = None;
+            let mut preferred = false;
+            for tr in graph.triples_matching([&term], [&rdfs::label], Any) {
+                let tr = tr?;
+                if let Some(val) = tr.o().lexical_form() {
+                    index.insert(id, Field::Label, &val);
+
+                    let is_preferred = tr.o().language_tag().is_none_or(|tag| tag.as_str() == "en");
+                    if !preferred && (is_preferred || label.is_none()) {
+                        label = Some(val.to_string());
+                        preferred = is_preferred;
+                    }
+                }
+            }
+
+            if let Some(skos) = skos {
+                for tr in graph.triples_matching([&term], [skos.get("definition")?], Any) {
+                    if let Some(val) = tr?.o().lexical_form() {
+                        index.insert(id, Field::Definition, &val);
+                    }
+                }
+            }
+
+            if let Some(brick) = brick {
+                for tr in graph.triples_matching([&term], [brick.get("hasAssociatedTag")?], Any) {
+                    if let Ok(tag) = Curie::from_term(tr?.o(), prefixes) {
+                        index.insert(id, Field::Tag, &tag.local_name);
+                    }
+                }
+            }
+
+            index.classes.push((class, label.unwrap_or_default()));
+        }
+
+        for token in index.tokens.keys() {
+            index
+                .lengths
+                .entry(token.chars().count())
+                .or_default()
+                .push(token.clone());
+        }
+
+        Ok(index)
+    }
+
+    fn insert(&mut self, id: usize, field: Field, text: &str) {
+        for token in tokenize(text) {
+            let postings = self.tokens.entry(token).or_default();
+            if !postings.contains(&(id, field)) {
+                postings.push((id, field));
+            }
+        }
+    }
+
+    /// The indexed tokens matching a query term, with the quality of the match:
+    /// exactly, as a prefix, or within a small number of typos.
+    fn matching_tokens(&self, term: &str) -> HashMap<&str, f64> {
+        let mut matches = HashMap::new();
+
+        if let Some((token, _)) = self.tokens.get_key_value(term) {
+            matches.insert(token.as_str(), 1.0);
+        }
+
+        let len = term.chars().count();
+
+        if len >= 2 {
+            for (token, _) in self
+                .tokens
+                .range::<str, _>((Bound::Excluded(term), Bound::Unbounded))
+                .take_while(|(token, _)| token.starts_with(term))
+            {
+                matches.insert(token.as_str(), 0.7);
+            }
+        }
+
+        let max_typos = match len {
+            0..=3 => 0,
+            4..=7 => 1,
+            _ => 2,
+        };
+
+        if max_typos > 0 {
+            for candidate_len in len.saturating_sub(max_typos)..=len + max_typos {
+                for token in self.lengths.get(&candidate_len).into_iter().flatten() {
+                    if !matches.contains_key(token.as_str())
+                        && edit_distance(term, token) <= max_typos
+                    {
+                        matches.insert(token.as_str(), 0.5);
+                    }
+                }
+            }
+        }
+
+        matches
+    }
+
+    pub(crate) fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        let query = tokenize(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores = HashMap::<usize, f64>::new();
+
+        for term in &query {
+            let mut best = HashMap::<usize, f64>::new();
+
+            for (token, quality) in self.matching_tokens(term) {
+                for (id, field) in &self.tokens[token] {
+                    let score = best.entry(*id).or_default();
+                    *score = score.max(quality * field.weight());
+                }
+            }
+
+            for (id, score) in best {
+                *scores.entry(id).or_default() += score;
+            }
+        }
+
+        let mut results = scores
+            .into_iter()
+            .map(|(id, score)| SearchResult {
+                class: self.classes[id].0.clone(),
+                label: self.classes[id].1.clone(),
+                score,
+            })
+            .collect::<Vec<SearchResult>>();
+
+        results.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| Ord::cmp(&a.class.local_name.len(), &b.class.local_name.len()))
+                .then_with(|| Ord::cmp(&a.class, &b.class))
+        });
+        results.truncate(limit);
+
+        results
+    }
+}
+
+/// Split a text in lower case words, on white space, punctuation and `_`.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// Levenshtein distance between two words.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut row = (0..=b.len()).collect::<Vec<usize>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(row[j + 1])
+            };
+            prev = cur;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        brick::{test::test_brick, Brick},
+        curie::Curie,
+    };
+
+    fn sample() -> Brick {
+        Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+            brick:Chiller a owl:Class ; rdfs:label "Chiller"@en, "Kältemaschine"@de .
+            brick:Boiler a owl:Class ; rdfs:label "Boiler" .
+            brick:Damper a owl:Class ; rdfs:label "Damper" .
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn displays_preferred_label() {
+        let results = sample().search("kältemaschine", 5);
+        assert_eq!(results[0].class, Curie::new("brick", "Chiller"));
+        assert_eq!(results[0].label, "Chiller");
+    }
+
+    #[test]
+    fn folds_equivalent_classes() {
+        let brick = Brick::new(
+            r#"
+            @prefix brick: <https://brickschema.org/schema/Brick#> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+            brick:Boiler a owl:Class ; rdfs:label "Boiler" .
+            brick:Heater a owl:Class ; rdfs:label "Heater" ; owl:equivalentClass brick:Boiler .
+            brick:Hot_Water_Heater a owl:Class ; rdfs:label "Hot Water Heater" ;
+                brick:aliasOf brick:Boiler .
+            "#,
+        )
+        .unwrap();
+
+        let results = brick.search("heater", 5);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].class, Curie::new("brick", "Boiler"));
+        assert_eq!(
+            brick
+                .canonical_class(&Curie::new("brick", "Heater"))
+                .unwrap(),
+            Curie::new("brick", "Boiler")
+        );
+    }
+
+    #[test]
+    fn matches_prefixes_and_typos() {
+        let brick = sample();
+
+        assert_eq!(
+            brick.search("boi", 5)[0].class,
+            Curie::new("brick", "Boiler")
+        );
+        assert_eq!(
+            brick.search("dampr", 5)[0].class,
+            Curie::new("brick", "Damper")
+        );
+        assert_eq!(
+            brick.search("chiler", 5)[0].class,
+            Curie::new("brick", "Chiller")
+        );
+        assert!(brick.search("xyz", 5).is_empty());
+    }
+
+    #[test]
+    fn searches_classes() {
+        let brick = test_brick();
+
+        let results = brick.search("supply air temperature sensor", 5);
+        assert_eq!(results.len(), 5);
+        assert_eq!(
+            results[0].class,
+            Curie::new("brick", "Supply_Air_Temperature_Sensor")
+        );
+        assert_eq!(results[0].label, "Supply Air Temperature Sensor");
+        assert!(results.windows(2).all(|w| w[0].score >= w[1].score));
+
+        let results = brick.search("chil", 10);
+        assert!(results
+            .iter()
+            .any(|r| r.class == Curie::new("brick", "Chiller")));
+
+        let results = brick.search("temprature sensr", 10);
+        assert_eq!(results[0].class, Curie::new("brick", "Temperature_Sensor"));
+
+        let results = brick.search("ahu", 5);
+        assert!(results
+            .iter()
+            .any(|r| r.class == Curie::new("brick", "Air_Handling_Unit")));
+        assert!(!results
+            .iter()
+            .any(|r| r.class == Curie::new("brick", "AHU")));
+
+        assert!(brick.search("  ", 10).is_empty());
+    }
+}
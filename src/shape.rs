@@ -0,0 +1,21 @@
+// Copyright (C) 2025 Radu Racariu.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{curie::Curie, property::BrickProperty};
+
+/// A SHACL node shape, independent of the class it may describe.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeShape {
+    pub shape: Curie,
+    pub target_classes: Vec<Curie>,
+    pub target_subjects_of: Vec<Curie>,
+    pub target_objects_of: Vec<Curie>,
+    pub severity: Option<Curie>,
+    pub message: String,
+    pub closed: bool,
+    pub ignored_properties: Vec<Curie>,
+    pub properties: Vec<BrickProperty>,
+}
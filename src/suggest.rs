@@ -0,0 +1,310 @@
+// Copyright (C) 2025 Radu Racariu.
+
+//!
+//! Suggest Brick point classes from raw point names, such as `AHU1_SAT` or `ZN-T-SP`.
+//!
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::{curie::Curie, hierarchy::ClassHierarchy, tags::TagIndex};
+
+/// A candidate class for a point name.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassSuggestion {
+    pub class: Curie,
+    pub score: f64,
+    pub matched_tags: Vec<String>,
+}
+
+/// Dictionary expanding the abbreviations found in point names to Brick tags.
+#[derive(Debug, Clone)]
+pub struct Abbreviations {
+    entries: HashMap<String, Vec<String>>,
+}
+
+impl Default for Abbreviations {
+    fn default() -> Self {
+        Abbreviations::new()
+    }
+}
+
+impl Abbreviations {
+    /// Create a dictionary of common building automation abbreviations.
+    pub fn new() -> Self {
+        let mut abbreviations = Abbreviations::empty();
+
+        for (abbr, tags) in [
+            ("SAT", "Supply Air Temperature Sensor"),
+            ("DAT", "Discharge Air Temperature Sensor"),
+            ("RAT", "Return Air Temperature Sensor"),
+            ("MAT", "Mixed Air Temperature Sensor"),
+            ("OAT", "Outside Air Temperature Sensor"),
+            ("EAT", "Exhaust Air Temperature Sensor"),
+            ("ZAT", "Zone Air Temperature Sensor"),
+            ("SAF", "Supply Air Flow"),
+            ("RAF", "Return Air Flow"),
+            ("OAF", "Outside Air Flow"),
+            ("SF", "Supply Fan"),
+            ("RF", "Return Fan"),
+            ("EF", "Exhaust Fan"),
+            ("CHW", "Chilled Water"),
+            ("HW", "Hot Water"),
+            ("CW", "Condenser Water"),
+            ("DHW", "Domestic Hot Water"),
+            ("ZN", "Zone"),
+            ("RM", "Room"),
+            ("T", "Temperature"),
+            ("TMP", "Temperature"),
+            ("TEMP", "Temperature"),
+            ("SP", "Setpoint"),
+            ("STPT", "Setpoint"),
+            ("SPT", "Setpoint"),
+            ("P", "Pressure"),
+            ("PRES", "Pressure"),
+            ("PRESS", "Pressure"),
+            ("DP", "Differential Pressure"),
+            ("RH", "Relative Humidity"),
+            ("HUM", "Humidity"),
+            ("FLW", "Flow"),
+            ("CMD", "Command"),
+            ("STS", "Status"),
+            ("STAT", "Status"),
+            ("ALM", "Alarm"),
+            ("ALRM", "Alarm"),
+            ("VLV", "Valve"),
+            ("DMP", "Damper"),
+            ("DMPR", "Damper"),
+            ("POS", "Position"),
+            ("SPD", "Speed"),
+            ("FREQ", "Frequency"),
+            ("OCC", "Occupancy"),
+            ("UNOCC", "Unoccupied"),
+            ("EN", "Enable"),
+            ("ENA", "Enable"),
+            ("KW", "Power"),
+            ("KWH", "Energy"),
+            ("HTG", "Heating"),
+            ("CLG", "Cooling"),
+            ("HI", "High"),
+            ("LO", "Low"),
+            ("MIN", "Min"),
+            ("MAX", "Max"),
+            ("SNS", "Sensor"),
+            ("SENS", "Sensor"),
+        ] {
+            abbreviations.insert(abbr, &tags.split(' ').collect::<Vec<&str>>());
+        }
+
+        abbreviations
+    }
+
+    /// Create an empty dictionary.
+    pub fn empty() -> Self {
+        Abbreviations {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Add or replace an abbreviation, matched case insensitively.
+    pub fn insert(&mut self, abbr: &str, tags: &[&str]) {
+        self.entries.insert(
+            abbr.to_uppercase(),
+            tags.iter().map(|tag| tag.to_string()).collect(),
+        );
+    }
+
+    /// The tags an abbreviation stands for.
+    pub fn expand(&self, abbr: &str) -> Option<&[String]> {
+        self.entries.get(&abbr.to_uppercase()).map(Vec::as_slice)
+    }
+}
+
+/// Suggest point classes by mapping the point name tokens to tags.
+///
+/// Tokens that are neither abbreviations nor tags of a point class, such as
+/// the equipment name `AHU` in `AHU1_SAT`, are dropped. Classes are mapped with
+/// `current`, which replaces aliases and deprecated classes or drops them, the
+/// best scored suggestion of a class is kept.
+pub(crate) fn suggest_classes(
+    tags: &TagIndex,
+    hierarchy: &ClassHierarchy,
+    current: impl Fn(&Curie) -> Option<Curie>,
+    point_name: &str,
+    abbreviations: &Abbreviations,
+    limit: usize,
+) -> Vec<ClassSuggestion> {
+    let point = Curie::new("brick", "Point");
+    let mut point_tags = vec!["Point".to_string()];
+
+    for token in tokenize(point_name) {
+        let expanded = match abbreviations.expand(&token) {
+            Some(expanded) => expanded.to_vec(),
+            None => tags
+                .name(&token)
+                .filter(|tag| {
+                    tags.classes_of(tag)
+                        .iter()
+                        .any(|class| hierarchy.is_subclass_of(class, &point))
+                })
+                .map(|tag| vec![tag.to_string()])
+                .unwrap_or_default(),
+        };
+
+        for tag in expanded {
+            if !point_tags.contains(&tag) {
+                point_tags.push(tag);
+            }
+        }
+    }
+
+    if point_tags.len() == 1 {
+        return Vec::new();
+    }
+
+    let mut seen = HashSet::new();
+    let inference =
+        tags.classes_for_tags(&point_tags.iter().map(String::as_str).collect::<Vec<_>>());
+
+    inference
+        .exact
+        .into_iter()
+        .map(|class| ClassSuggestion {
+            class,
+            score: 1.0,
+            matched_tags: point_tags.clone(),
+        })
+        .chain(
+            inference
+                .partial
+                .into_iter()
+                .map(|tag_match| ClassSuggestion {
+                    score: 2.0 * tag_match.precision * tag_match.recall
+                        / (tag_match.precision + tag_match.recall),
+                    class: tag_match.class,
+                    matched_tags: tag_match.matched_tags,
+                }),
+        )
+        .filter_map(|suggestion| {
+            Some(ClassSuggestion {
+                class: current(&suggestion.class)?,
+                ..suggestion
+            })
+        })
+        .filter(|suggestion| {
+            hierarchy.is_subclass_of(&suggestion.class, &point)
+                && seen.insert(suggestion.class.clone())
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Split a point name on separators, case changes and digits, dropping numbers.
+fn tokenize(name: &str) -> Vec<String> {
+    let mut tokens = Vec::<String>::new();
+    let mut cur = String::new();
+    let mut prev: Option<char> = None;
+
+    for c in name.chars() {
+        let boundary = match prev {
+            _ if !c.is_alphanumeric() => true,
+            Some(p) => {
+                (p.is_lowercase() && c.is_uppercase())
+                    || (p.is_alphabetic() && c.is_numeric())
+                    || (p.is_numeric() && c.is_alphabetic())
+            }
+            None => false,
+        };
+
+        if boundary && !cur.is_empty() {
+            tokens.push(std::mem::take(&mut cur));
+        }
+
+        if c.is_alphanumeric() {
+            cur.push(c);
+        }
+        prev = Some(c);
+    }
+
+    if !cur.is_empty() {
+        tokens.push(cur);
+    }
+
+    tokens.retain(|token| !token.chars().all(char::is_numeric));
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brick::test::test_brick;
+
+    #[test]
+    fn tokenizes_point_names() {
+        assert_eq!(tokenize("AHU1_SAT"), ["AHU", "SAT"]);
+        assert_eq!(tokenize("ZN-T-SP"), ["ZN", "T", "SP"]);
+        assert_eq!(tokenize("Vav12.ZnTempSp"), ["Vav", "Zn", "Temp", "Sp"]);
+        assert!(tokenize("__12__").is_empty());
+    }
+
+    #[test]
+    fn expands_abbreviations() {
+        let mut abbreviations = Abbreviations::new();
+        assert_eq!(abbreviations.expand("sp").unwrap(), ["Setpoint"]);
+        assert!(abbreviations.expand("XYZ").is_none());
+
+        abbreviations.insert("xyz", &["Supply", "Fan"]);
+        assert_eq!(abbreviations.expand("XYZ").unwrap(), ["Supply", "Fan"]);
+
+        assert!(Abbreviations::empty().expand("SP").is_none());
+        assert_eq!(
+            Abbreviations::default().expand("SP"),
+            Abbreviations::new().expand("SP")
+        );
+    }
+
+    #[test]
+    fn suggests_point_classes() {
+        let brick = test_brick();
+
+        let suggestions = brick.suggest_classes("AHU1_SAT", 5);
+        assert!(suggestions
+            .iter()
+            .any(|s| s.class == Curie::new("brick", "Supply_Air_Temperature_Sensor")));
+        assert!(suggestions
+            .iter()
+            .all(|s| !s.matched_tags.contains(&"AHU".to_string())));
+        assert!(suggestions[0]
+            .matched_tags
+            .contains(&"Temperature".to_string()));
+        assert!(suggestions.windows(2).all(|w| w[0].score >= w[1].score));
+
+        let suggestions = brick.suggest_classes("ZN-T-SP", 5);
+        assert!(suggestions
+            .iter()
+            .all(|s| s.class.local_name.ends_with("Temperature_Setpoint")));
+        assert!(suggestions
+            .iter()
+            .any(|s| s.class == Curie::new("brick", "Target_Zone_Air_Temperature_Setpoint")));
+        assert!(suggestions
+            .iter()
+            .all(|s| s.class != Curie::new("brick", "Zone_Air_Temperature_Setpoint")));
+        let deprecated = brick.deprecated_classes().unwrap();
+        assert!(suggestions.iter().all(|s| {
+            deprecated.iter().all(|d| d.class != s.class)
+                && brick.canonical_class(&s.class).unwrap() == s.class
+        }));
+
+        let mut abbreviations = crate::suggest::Abbreviations::empty();
+        abbreviations.insert("HUMID", &["Relative", "Humidity", "Sensor"]);
+        let suggestions = brick.suggest_classes_with("RM101-HUMID", &abbreviations, 3);
+        assert_eq!(
+            suggestions[0].class,
+            Curie::new("brick", "Relative_Humidity_Sensor")
+        );
+
+        assert!(brick.suggest_classes("12-34", 3).is_empty());
+    }
+}
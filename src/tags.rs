@@ -0,0 +1,230 @@
+// Copyright (C) 2025 Radu Racariu.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sophia::inmem::graph::FastGraph;
+use sophia_api::{graph::Graph, term::matcher::Any, triple::Triple};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{curie::Curie, namespaces::PrefixNamespaceMap};
+
+/// A Brick tag with its usage.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagInfo {
+    pub name: String,
+    pub label: String,
+    pub definition: String,
+    /// Number of classes associated with the tag.
+    pub class_count: u32,
+}
+
+/// A class partially matching a set of tags.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagMatch {
+    pub class: Curie,
+    pub matched_tags: Vec<String>,
+    /// Ratio of the class tags found in the input.
+    pub precision: f64,
+    /// Ratio of the input tags found on the class.
+    pub recall: f64,
+}
+
+/// The classes inferred from a set of tags.
+#[cfg_attr(target_arch = "wasm32", derive(tsify::Tsify))]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagInference {
+    /// Classes having exactly the input tags.
+    pub exact: Vec<Curie>,
+    /// Classes sharing some of the input tags, best matches first.
+    pub partial: Vec<TagMatch>,
+}
+
+/// Inverted index of the `brick:hasAssociatedTag` relations.
+#[derive(Default, Debug)]
+pub(crate) struct TagIndex {
+    /// Tags of each class, by their lower case name.
+    class_tags: HashMap<Curie, BTreeSet<String>>,
+    /// Classes of each lower case tag name.
+    tag_classes: HashMap<String, Vec<Curie>>,
+    /// Tag names as declared, by their lower case name.
+    names: HashMap<String, String>,
+}
+
+impl TagIndex {
+    pub(crate) fn new(graph: &FastGraph, prefixes: &PrefixNamespaceMap) -> Result<Self> {
+        let mut index = TagIndex::default();
+
+        let Some(brick) = prefixes.get_ns("brick") else {
+            return Ok(index);
+        };
+
+        for triple in graph.triples_matching(Any, [brick.get("hasAssociatedTag")?], Any) {
+            let triple = triple?;
+
+            let (Ok(class), Ok(tag)) = (
+                Curie::from_term(triple.s(), prefixes),
+                Curie::from_term(triple.o(), prefixes),
+            ) else {
+                continue;
+            };
+
+            let key = tag.local_name.to_lowercase();
+
+            index
+                .class_tags
+                .entry(class.clone())
+                .or_default()
+                .insert(key.clone());
+            index
+                .tag_classes
+                .entry(key.clone())
+                .or_default()
+                .push(class);
+            index.names.entry(key).or_insert(tag.local_name);
+        }
+
+        for classes in index.tag_classes.values_mut() {
+            classes.sort();
+            classes.dedup();
+        }
+
+        Ok(index)
+    }
+
+    /// Number of classes associated with a tag.
+    pub(crate) fn class_count(&self, tag: &str) -> u32 {
+        self.tag_classes
+            .get(&tag.to_lowercase())
+            .map_or(0, |classes| classes.len() as u32)
+    }
+
+    /// The classes associated with a tag, looked up case insensitively.
+    pub(crate) fn classes_of(&self, tag: &str) -> &[Curie] {
+        self.tag_classes
+            .get(&tag.to_lowercase())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The declared name of a tag, looked up case insensitively.
+    pub(crate) fn name(&self, tag: &str) -> Option<&str> {
+        self.names.get(&tag.to_lowercase()).map(String::as_str)
+    }
+
+    pub(crate) fn classes_for_tags(&self, tags: &[&str]) -> TagInference {
+        let tags = tags
+            .iter()
+            .map(|tag| tag.trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect::<BTreeSet<String>>();
+
+        let mut candidates = BTreeSet::<&Curie>::new();
+        for tag in &tags {
+            if let Some(classes) = self.tag_classes.get(tag) {
+                candidates.extend(classes);
+            }
+        }
+
+        let mut inference = TagInference::default();
+
+        for class in candidates {
+            let class_tags = &self.class_tags[class];
+
+            if *class_tags == tags {
+                inference.exact.push(class.clone());
+                continue;
+            }
+
+            let matched_tags = class_tags
+                .intersection(&tags)
+                .map(|tag| self.names[tag].clone())
+                .collect::<Vec<String>>();
+
+            inference.partial.push(TagMatch {
+                class: class.clone(),
+                precision: matched_tags.len() as f64 / class_tags.len() as f64,
+                recall: matched_tags.len() as f64 / tags.len() as f64,
+                matched_tags,
+            });
+        }
+
+        inference.partial.sort_by(|a, b| {
+            f1_score(b)
+                .total_cmp(&f1_score(a))
+                .then_with(|| b.precision.total_cmp(&a.precision))
+                .then_with(|| a.class.cmp(&b.class))
+        });
+
+        inference
+    }
+}
+
+fn f1_score(tag_match: &TagMatch) -> f64 {
+    2.0 * tag_match.precision * tag_match.recall / (tag_match.precision + tag_match.recall)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brick::test::test_brick;
+
+    #[test]
+    fn infers_classes_from_tags() {
+        let brick = test_brick();
+
+        let inference = brick.classes_for_tags(&[
+            "Air",
+            "Temperature",
+            "Sensor",
+            "Point",
+            "supply",
+            "Discharge",
+        ]);
+        assert!(inference
+            .exact
+            .contains(&Curie::new("brick", "Supply_Air_Temperature_Sensor")));
+        assert!(!inference
+            .partial
+            .iter()
+            .any(|m| m.class == Curie::new("brick", "Supply_Air_Temperature_Sensor")));
+
+        let f1 =
+            |m: &crate::tags::TagMatch| 2.0 * m.precision * m.recall / (m.precision + m.recall);
+        assert!(inference.partial.windows(2).all(|w| f1(&w[0]) >= f1(&w[1])));
+
+        let sensor = inference
+            .partial
+            .iter()
+            .find(|m| m.class == Curie::new("brick", "Temperature_Sensor"))
+            .unwrap();
+        assert_eq!(sensor.precision, 1.0);
+        assert_eq!(sensor.recall, 0.5);
+        assert_eq!(sensor.matched_tags, ["Point", "Sensor", "Temperature"]);
+
+        let inference = brick.classes_for_tags(&["Unknown"]);
+        assert!(inference.exact.is_empty() && inference.partial.is_empty());
+    }
+
+    #[test]
+    fn lists_tag_catalogue() {
+        let brick = test_brick();
+
+        let tags = brick.tags().unwrap();
+        assert!(tags.len() > 500);
+        assert!(tags.windows(2).all(|w| w[0].name < w[1].name));
+
+        let air = tags.iter().find(|t| t.name == "Air").unwrap();
+        assert_eq!(air.label, "Air");
+        assert!(air.class_count > 100);
+
+        let sensor = brick.tag("sensor").unwrap();
+        assert_eq!(sensor.name, "Sensor");
+        assert!(sensor.class_count > 100);
+
+        assert!(brick.tag("Unknown").is_err());
+    }
+}
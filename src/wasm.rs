@@ -4,16 +4,17 @@
 //! This module provides a WebAssembly interface for the FeBrick crate.
 //!
 
-use crate::{brick::Brick as BrickImpl, curie::Curie};
+use crate::{brick::Brick as BrickImpl, curie::Curie, model::BrickModel as BrickModelImpl};
 use log::info;
 use serde_wasm_bindgen::{from_value, to_value};
-use std::panic;
+use std::{panic, rc::Rc};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_console_logger::DEFAULT_LOGGER;
 
 #[wasm_bindgen]
 pub struct Brick {
-    brick: BrickImpl,
+    /// Shared with the models checked against the ontology.
+    brick: Rc<BrickImpl>,
 }
 
 #[wasm_bindgen]
@@ -24,10 +25,92 @@ impl Brick {
     #[wasm_bindgen(constructor)]
     pub fn new(ttl: &str) -> Result<Brick, String> {
         Ok(Brick {
-            brick: BrickImpl::new(ttl).map_err(|err| err.to_string())?,
+            brick: Rc::new(BrickImpl::new(ttl).map_err(|err| err.to_string())?),
         })
     }
 
+    /// Load more Turtle in the ontology, such as the QUDT unit and quantity kind vocabularies.
+    /// Fails once models were created from the ontology.
+    /// # Arguments
+    /// * `ttl` - A Turtle file content.
+    #[wasm_bindgen(js_name = load)]
+    pub fn load(&mut self, ttl: &str) -> Result<(), String> {
+        Rc::get_mut(&mut self.brick)
+            .ok_or_else(|| "The ontology is in use by a model".to_string())?
+            .load(ttl)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Return all the classes declared in the ontology.
+    #[wasm_bindgen(js_name = classes, unchecked_return_type = "Curie[]")]
+    pub fn classes(&self) -> Result<Vec<JsValue>, String> {
+        self.brick
+            .classes()
+            .map_err(|err| err.to_string())
+            .and_then(|vec| {
+                vec.into_iter()
+                    .map(|curie| to_value(&curie))
+                    .collect::<Result<_, _>>()
+                    .map_err(|err| err.to_string())
+            })
+    }
+
+    /// Return all the relationships declared in the ontology.
+    #[wasm_bindgen(js_name = objectProperties, unchecked_return_type = "Curie[]")]
+    pub fn object_properties(&self) -> Result<Vec<JsValue>, String> {
+        self.brick
+            .object_properties()
+            .map_err(|err| err.to_string())
+            .and_then(|vec| {
+                vec.into_iter()
+                    .map(|curie| to_value(&curie))
+                    .collect::<Result<_, _>>()
+                    .map_err(|err| err.to_string())
+            })
+    }
+
+    /// Return all the datatype properties declared in the ontology.
+    #[wasm_bindgen(js_name = datatypeProperties, unchecked_return_type = "Curie[]")]
+    pub fn datatype_properties(&self) -> Result<Vec<JsValue>, String> {
+        self.brick
+            .datatype_properties()
+            .map_err(|err| err.to_string())
+            .and_then(|vec| {
+                vec.into_iter()
+                    .map(|curie| to_value(&curie))
+                    .collect::<Result<_, _>>()
+                    .map_err(|err| err.to_string())
+            })
+    }
+
+    /// Return the classes that have no superclass in the ontology.
+    #[wasm_bindgen(js_name = rootClasses, unchecked_return_type = "Curie[]")]
+    pub fn root_classes(&self) -> Result<Vec<JsValue>, String> {
+        self.brick
+            .root_classes()
+            .map_err(|err| err.to_string())
+            .and_then(|vec| {
+                vec.into_iter()
+                    .map(|curie| to_value(&curie))
+                    .collect::<Result<_, _>>()
+                    .map_err(|err| err.to_string())
+            })
+    }
+
+    /// Return the top level Brick categories, such as Point, Equipment or Location.
+    #[wasm_bindgen(js_name = topLevelCategories, unchecked_return_type = "Curie[]")]
+    pub fn top_level_categories(&self) -> Result<Vec<JsValue>, String> {
+        self.brick
+            .top_level_categories()
+            .map_err(|err| err.to_string())
+            .and_then(|vec| {
+                vec.into_iter()
+                    .map(|curie| to_value(&curie))
+                    .collect::<Result<_, _>>()
+                    .map_err(|err| err.to_string())
+            })
+    }
+
     /// For the given class, return all of its subclasses names.
     #[wasm_bindgen(js_name = subClassOf, unchecked_return_type = "Curie[]")]
     pub fn sub_classes_of(
@@ -66,6 +149,95 @@ impl Brick {
             })
     }
 
+    /// For the given class or alias, return its canonical class.
+    #[wasm_bindgen(js_name = canonicalClass, unchecked_return_type = "Curie")]
+    pub fn canonical_class(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] curie: JsValue,
+    ) -> Result<JsValue, String> {
+        let curie = from_value(curie).map_err(|err| err.to_string())?;
+
+        self.brick
+            .canonical_class(&curie)
+            .map_err(|err| err.to_string())
+            .and_then(|curie| to_value(&curie).map_err(|err| err.to_string()))
+    }
+
+    /// For the given class, return all of its aliases.
+    #[wasm_bindgen(js_name = aliasesOf, unchecked_return_type = "Curie[]")]
+    pub fn aliases_of(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] curie: JsValue,
+    ) -> Result<Vec<JsValue>, String> {
+        let curie = from_value(curie).map_err(|err| err.to_string())?;
+
+        self.brick
+            .aliases_of(&curie)
+            .map_err(|err| err.to_string())
+            .and_then(|vec| {
+                vec.into_iter()
+                    .map(|curie| to_value(&curie))
+                    .collect::<Result<_, _>>()
+                    .map_err(|err| err.to_string())
+            })
+    }
+
+    /// For the given class, return all of its transitive subclasses with their distance.
+    /// # Arguments
+    /// * `curie` - The class to start from.
+    /// * `all_paths` - Report every derivation path instead of only a shortest one.
+    #[wasm_bindgen(js_name = descendantsOf, unchecked_return_type = "ClassRelative[]")]
+    pub fn descendants_of(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] curie: JsValue,
+        all_paths: bool,
+    ) -> Result<JsValue, String> {
+        let curie = from_value(curie).map_err(|err| err.to_string())?;
+
+        self.brick
+            .descendants_of(&curie, all_paths)
+            .map_err(|err| err.to_string())
+            .and_then(|vec| to_value(&vec).map_err(|err| err.to_string()))
+    }
+
+    /// For the given class, return all of its transitive superclasses with their distance.
+    /// # Arguments
+    /// * `curie` - The class to start from.
+    /// * `all_paths` - Report every derivation path instead of only a shortest one.
+    #[wasm_bindgen(js_name = ancestorsOf, unchecked_return_type = "ClassRelative[]")]
+    pub fn ancestors_of(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] curie: JsValue,
+        all_paths: bool,
+    ) -> Result<JsValue, String> {
+        let curie = from_value(curie).map_err(|err| err.to_string())?;
+
+        self.brick
+            .ancestors_of(&curie, all_paths)
+            .map_err(|err| err.to_string())
+            .and_then(|vec| to_value(&vec).map_err(|err| err.to_string()))
+    }
+
+    /// Return the whole subclass tree of the given class in one call.
+    /// # Arguments
+    /// * `root` - The class at the root of the tree.
+    /// * `max_depth` - Optional maximum depth of the tree.
+    /// * `all_parents` - List a class under each of its parents, not only the first one.
+    #[wasm_bindgen(js_name = classTree, unchecked_return_type = "ClassNode")]
+    pub fn class_tree(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] root: JsValue,
+        max_depth: Option<u32>,
+        all_parents: bool,
+    ) -> Result<JsValue, String> {
+        let root = from_value(root).map_err(|err| err.to_string())?;
+
+        self.brick
+            .class_tree(&root, max_depth, all_parents)
+            .map_err(|err| err.to_string())
+            .and_then(|tree| to_value(&tree).map_err(|err| err.to_string()))
+    }
+
     /// For the given class, return all of its tags.
     #[wasm_bindgen(js_name = classTags)]
     pub fn class_tags(
@@ -80,6 +252,64 @@ impl Brick {
             .map_err(|err| err.to_string())?)
     }
 
+    /// Search the classes matching the query, best matches first.
+    /// # Arguments
+    /// * `query` - Free text, matched against class names, labels, definitions and tags.
+    /// * `limit` - Maximum number of results.
+    #[wasm_bindgen(js_name = search, unchecked_return_type = "SearchResult[]")]
+    pub fn search(&self, query: &str, limit: usize) -> Result<JsValue, String> {
+        to_value(&self.brick.search(query, limit)).map_err(|err| err.to_string())
+    }
+
+    /// Return all the deprecated classes with their replacement.
+    #[wasm_bindgen(js_name = deprecatedClasses, unchecked_return_type = "DeprecatedClass[]")]
+    pub fn deprecated_classes(&self) -> Result<JsValue, String> {
+        self.brick
+            .deprecated_classes()
+            .map_err(|err| err.to_string())
+            .and_then(|vec| to_value(&vec).map_err(|err| err.to_string()))
+    }
+
+    /// Migrate a Turtle model away from the deprecated classes and properties.
+    ///
+    /// # Arguments
+    /// * `model` - The model, as Turtle.
+    #[wasm_bindgen(js_name = migrateModel, unchecked_return_type = "MigrationReport")]
+    pub fn migrate_model(&self, model: &str) -> Result<JsValue, String> {
+        self.brick
+            .migrate_model(model)
+            .map_err(|err| err.to_string())
+            .and_then(|report| to_value(&report).map_err(|err| err.to_string()))
+    }
+
+    /// Suggest point classes for a raw point name, best candidates first.
+    ///
+    /// # Arguments
+    /// * `point_name` - A point name such as `AHU1_SAT` or `ZN-T-SP`.
+    /// * `limit` - Maximum number of suggestions.
+    #[wasm_bindgen(js_name = suggestClasses, unchecked_return_type = "ClassSuggestion[]")]
+    pub fn suggest_classes(&self, point_name: &str, limit: usize) -> Result<JsValue, String> {
+        to_value(&self.brick.suggest_classes(point_name, limit)).map_err(|err| err.to_string())
+    }
+
+    /// Return all the tags declared in the ontology with their usage.
+    #[wasm_bindgen(js_name = tags, unchecked_return_type = "TagInfo[]")]
+    pub fn tags(&self) -> Result<JsValue, String> {
+        self.brick
+            .tags()
+            .map_err(|err| err.to_string())
+            .and_then(|vec| to_value(&vec).map_err(|err| err.to_string()))
+    }
+
+    /// For the given tag name, return its definition and usage.
+    #[wasm_bindgen(js_name = tag, unchecked_return_type = "TagInfo")]
+    pub fn tag(&self, name: &str) -> Result<JsValue, String> {
+        self.brick
+            .tag(name)
+            .map_err(|err| err.to_string())
+            .and_then(|tag| to_value(&tag).map_err(|err| err.to_string()))
+    }
+
     /// For the given class curie, return its core definition.
     #[wasm_bindgen(js_name = classDescription, unchecked_return_type = "BrickEntity")]
     pub fn class_description(
@@ -94,6 +324,151 @@ impl Brick {
             .and_then(|vec| to_value(&vec).map_err(|err| err.to_string()))
     }
 
+    /// Return the point classes measuring a substance and a quantity.
+    /// # Arguments
+    /// * `substance` - A substance such as `brick:Supply_Air`, or `undefined` for any.
+    /// * `quantity` - A quantity such as `quantitykind:Temperature`, or `undefined` for any.
+    #[wasm_bindgen(js_name = pointsFor, unchecked_return_type = "Curie[]")]
+    pub fn points_for(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie | undefined")] substance: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "Curie | undefined")] quantity: JsValue,
+    ) -> Result<JsValue, String> {
+        let substance: Option<Curie> = from_value(substance).map_err(|err| err.to_string())?;
+        let quantity: Option<Curie> = from_value(quantity).map_err(|err| err.to_string())?;
+
+        self.brick
+            .points_for(substance.as_ref(), quantity.as_ref())
+            .map_err(|err| err.to_string())
+            .and_then(|vec| to_value(&vec).map_err(|err| err.to_string()))
+    }
+
+    /// For the given point class, return the quantity it measures, if any.
+    #[wasm_bindgen(js_name = quantityOf, unchecked_return_type = "QuantityKind | undefined")]
+    pub fn quantity_of(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] curie: JsValue,
+    ) -> Result<JsValue, String> {
+        let curie = from_value(curie).map_err(|err| err.to_string())?;
+
+        self.brick
+            .quantity_of(&curie)
+            .map_err(|err| err.to_string())
+            .and_then(|quantity| to_value(&quantity).map_err(|err| err.to_string()))
+    }
+
+    /// For the given point class or quantity kind, return its applicable units.
+    #[wasm_bindgen(js_name = applicableUnits, unchecked_return_type = "Curie[]")]
+    pub fn applicable_units(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] curie: JsValue,
+    ) -> Result<JsValue, String> {
+        let curie = from_value(curie).map_err(|err| err.to_string())?;
+
+        self.brick
+            .applicable_units(&curie)
+            .map_err(|err| err.to_string())
+            .and_then(|vec| to_value(&vec).map_err(|err| err.to_string()))
+    }
+
+    /// For the given unit, return its symbol, dimension vector and conversion factors.
+    #[wasm_bindgen(js_name = unit, unchecked_return_type = "Unit")]
+    pub fn unit(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] curie: JsValue,
+    ) -> Result<JsValue, String> {
+        let curie = from_value(curie).map_err(|err| err.to_string())?;
+
+        self.brick
+            .unit(&curie)
+            .map_err(|err| err.to_string())
+            .and_then(|unit| to_value(&unit).map_err(|err| err.to_string()))
+    }
+
+    /// Convert a value between two units of the same dimension.
+    #[wasm_bindgen(js_name = convert)]
+    pub fn convert(
+        &self,
+        value: f64,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] from_unit: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] to_unit: JsValue,
+    ) -> Result<f64, String> {
+        let from_unit = from_value(from_unit).map_err(|err| err.to_string())?;
+        let to_unit = from_value(to_unit).map_err(|err| err.to_string())?;
+
+        self.brick
+            .convert(value, &from_unit, &to_unit)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Check if a unit can be used for a point class or a quantity kind.
+    #[wasm_bindgen(js_name = isUnitApplicable)]
+    pub fn is_unit_applicable(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] curie: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] unit: JsValue,
+    ) -> Result<bool, String> {
+        let curie = from_value(curie).map_err(|err| err.to_string())?;
+        let unit = from_value(unit).map_err(|err| err.to_string())?;
+
+        self.brick
+            .is_unit_applicable(&curie, &unit)
+            .map_err(|err| err.to_string())
+    }
+
+    /// For the given entity property, return its datatypes, units, quantity kind and enumeration.
+    #[wasm_bindgen(
+        js_name = entityPropertyDescription,
+        unchecked_return_type = "EntityPropertyDesc"
+    )]
+    pub fn entity_property_description(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] curie: JsValue,
+    ) -> Result<JsValue, String> {
+        let curie = from_value(curie).map_err(|err| err.to_string())?;
+
+        self.brick
+            .entity_property_desc(&curie)
+            .map_err(|err| err.to_string())
+            .and_then(|desc| to_value(&desc).map_err(|err| err.to_string()))
+    }
+
+    /// Return the relationships allowed from a subject class to an object class.
+    #[wasm_bindgen(
+        js_name = allowedRelationships,
+        unchecked_return_type = "AllowedRelationship[]"
+    )]
+    pub fn allowed_relationships(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] subject: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] object: JsValue,
+    ) -> Result<JsValue, String> {
+        let subject = from_value(subject).map_err(|err| err.to_string())?;
+        let object = from_value(object).map_err(|err| err.to_string())?;
+
+        self.brick
+            .allowed_relationships(&subject, &object)
+            .map_err(|err| err.to_string())
+            .and_then(|vec| to_value(&vec).map_err(|err| err.to_string()))
+    }
+
+    /// For the given relationship, return its inverse, domain, range and characteristics.
+    #[wasm_bindgen(
+        js_name = relationshipDescription,
+        unchecked_return_type = "BrickRelationship"
+    )]
+    pub fn relationship_description(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] curie: JsValue,
+    ) -> Result<JsValue, String> {
+        let curie = from_value(curie).map_err(|err| err.to_string())?;
+
+        self.brick
+            .relationship_desc(&curie)
+            .map_err(|err| err.to_string())
+            .and_then(|desc| to_value(&desc).map_err(|err| err.to_string()))
+    }
+
     /// For the given class, return all of its properties names.
     #[wasm_bindgen(js_name = classProperties, unchecked_return_type = "BrickProperty[]")]
     pub fn class_properties(
@@ -109,6 +484,134 @@ impl Brick {
     }
 }
 
+#[wasm_bindgen]
+pub struct BrickModel {
+    model: BrickModelImpl<Rc<BrickImpl>>,
+}
+
+#[wasm_bindgen]
+impl BrickModel {
+    /// Create an empty Brick model.
+    /// # Arguments
+    /// * `brick` - The ontology the model is checked against, shared with the model.
+    /// * `prefix` - The prefix of the model namespace.
+    /// * `namespace` - The namespace the model entities are named in.
+    #[wasm_bindgen(constructor)]
+    pub fn new(brick: &Brick, prefix: &str, namespace: &str) -> Result<BrickModel, String> {
+        Ok(BrickModel {
+            model: BrickModelImpl::new(brick.brick.clone(), prefix, namespace)
+                .map_err(|err| err.to_string())?,
+        })
+    }
+
+    /// Return the ids of the model entities.
+    #[wasm_bindgen(js_name = entities)]
+    pub fn entities(&self) -> Result<Vec<String>, String> {
+        self.model.entities().map_err(|err| err.to_string())
+    }
+
+    /// Describe an entity, with its types, relationships and property values.
+    #[wasm_bindgen(js_name = entity, unchecked_return_type = "ModelEntity")]
+    pub fn entity(&self, id: &str) -> Result<JsValue, String> {
+        self.model
+            .entity(id)
+            .map_err(|err| err.to_string())
+            .and_then(|entity| to_value(&entity).map_err(|err| err.to_string()))
+    }
+
+    /// Add an entity of the given class.
+    #[wasm_bindgen(js_name = addEntity)]
+    pub fn add_entity(
+        &mut self,
+        id: &str,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] class: JsValue,
+    ) -> Result<(), String> {
+        let class = from_value(class).map_err(|err| err.to_string())?;
+
+        self.model
+            .add_entity(id, &class)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Remove an entity with its properties and relationships.
+    #[wasm_bindgen(js_name = removeEntity)]
+    pub fn remove_entity(&mut self, id: &str) -> Result<(), String> {
+        self.model.remove_entity(id).map_err(|err| err.to_string())
+    }
+
+    /// Relate two entities, if the ontology allows the relationship.
+    #[wasm_bindgen(js_name = relate)]
+    pub fn relate(
+        &mut self,
+        subject: &str,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] relationship: JsValue,
+        object: &str,
+    ) -> Result<(), String> {
+        let relationship = from_value(relationship).map_err(|err| err.to_string())?;
+
+        self.model
+            .relate(subject, &relationship, object)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Remove a relationship between two entities.
+    #[wasm_bindgen(js_name = removeRelationship)]
+    pub fn remove_relationship(
+        &mut self,
+        subject: &str,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] relationship: JsValue,
+        object: &str,
+    ) -> Result<(), String> {
+        let relationship = from_value(relationship).map_err(|err| err.to_string())?;
+
+        self.model
+            .remove_relationship(subject, &relationship, object)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Set the value of an entity property, checked against its value shape.
+    #[wasm_bindgen(js_name = setProperty)]
+    pub fn set_property(
+        &mut self,
+        entity: &str,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] property: JsValue,
+        value: &str,
+        #[wasm_bindgen(unchecked_param_type = "Curie | undefined")] unit: JsValue,
+    ) -> Result<(), String> {
+        let property = from_value(property).map_err(|err| err.to_string())?;
+        let unit = from_value::<Option<Curie>>(unit).map_err(|err| err.to_string())?;
+
+        self.model
+            .set_property(entity, &property, value, unit.as_ref())
+            .map_err(|err| err.to_string())
+    }
+
+    /// Remove the value of an entity property.
+    #[wasm_bindgen(js_name = removeProperty)]
+    pub fn remove_property(
+        &mut self,
+        entity: &str,
+        #[wasm_bindgen(unchecked_param_type = "Curie")] property: JsValue,
+    ) -> Result<(), String> {
+        let property = from_value(property).map_err(|err| err.to_string())?;
+
+        self.model
+            .remove_property(entity, &property)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Write the model as Turtle, N-Triples, N-Quads, JSON-LD or RDF/XML.
+    #[wasm_bindgen(js_name = export)]
+    pub fn export(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "ModelFormat")] format: JsValue,
+    ) -> Result<String, String> {
+        let format = from_value(format).map_err(|err| err.to_string())?;
+
+        self.model.export(format).map_err(|err| err.to_string())
+    }
+}
+
 #[wasm_bindgen(start)]
 pub fn start() {
     panic::set_hook(Box::new(console_error_panic_hook::hook));